@@ -1,70 +1,53 @@
+extern crate day01;
+extern crate env_logger;
 #[macro_use]
 extern crate log;
-extern crate env_logger;
 
+use std::env;
 use std::error::Error;
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::Path;
 
+use day01::offset_sums;
 
-fn compare_and_cast (primary: char, secondary: char) -> u32 {
-    debug!("{} -> {}", primary, secondary);
-    if primary == secondary {
-        return primary.to_string().parse::<u32>().unwrap();
-    } else {
-        return 0;
-    }
-}
-
-fn main() {
-    let _logger = env_logger::init();
+/// Usage: day01 [path] [offset...]
+///
+/// Each offset is a number or `half` for half the captcha's length. With no
+/// offsets, solves part 1 (offset 1) and part 2 (offset `half`).
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
 
-    let path = Path::new("data/input");
+    let args: Vec<String> = env::args().collect();
+    let path = Path::new(args.get(1).map_or("data/input", String::as_str));
     let display = path.display();
 
-    // Open the path in read-only mode, returns `io::Result<File>`
-    let mut file = match File::open(&path) {
-        // The `description` method of `io::Error` returns a string that
-        // describes the error
-        Err(why) => panic!("couldn't open {}: {}", display,
-                                                   why.description()),
-        Ok(file) => file,
-    };
-
-    // Read the file contents into a string, returns `io::Result<usize>`
     let mut captcha = String::new();
-    match file.read_to_string(&mut captcha) {
-        Err(why) => panic!("couldn't read {}: {}", display,
-                                                   why.description()),
-        Ok(_) => debug!("{}", captcha),
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut captcha))
+        .map_err(|why| format!("couldn't read {}: {}", display, why))?;
+    debug!("{}", captcha);
+
+    let half = captcha.trim().chars().count() / 2;
+    if args.len() <= 2 {
+        let sums = offset_sums(&captcha, &[1, half])?;
+        println!("The answer to part1 is {}", sums[0]);
+        println!("The answer to part2 is {}", sums[1]);
+        return Ok(());
     }
-    let captcha = captcha.trim(); // remove the trailing whitespace
 
-    let mut sum: u32 = 0;
-    for (primary_char, secondary_char) in captcha.chars().zip(captcha.chars().cycle().skip(1)) {
-        sum = sum + compare_and_cast (primary_char, secondary_char);
-        debug!("sum now == {}", sum);
+    let offsets = args[2..]
+        .iter()
+        .map(|arg| match arg.as_str() {
+            "half" => Ok(half),
+            _ => arg
+                .parse::<usize>()
+                .map_err(|_| format!("invalid offset '{}'", arg)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let sums = offset_sums(&captcha, &offsets)?;
+    for (offset, sum) in offsets.iter().zip(sums) {
+        println!("The sum for offset {} is {}", offset, sum);
     }
-
-    print!("The answer to part1 is {}\n", sum);
-
-    // Problem states that our captcha should be of even length, double check
-    assert_eq! (captcha.len() % 2, 0);
-
-    // Figure out the starting position of the 2nd iterator
-    let half_captcha_len = captcha.len() / 2;
-    let half_way_iter = captcha.chars().cycle().skip(half_captcha_len);
-
-    let mut sum: u32 = 0;
-    for (primary_char, secondary_char) in captcha.chars().take(half_captcha_len).zip(half_way_iter) {
-        // Since the comparison is symmetric (we will compare index 0
-        // with index n/2 and then later on index n/2 with index 0),
-        // we can perform half of the comparisons and just double any
-        // valid summations
-        sum = sum + 2 * compare_and_cast (primary_char, secondary_char);
-        debug!("sum now == {}", sum);
-    }
-
-    print!("The answer to part2 is {}\n", sum);
+    Ok(())
 }
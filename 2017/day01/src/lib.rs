@@ -0,0 +1,101 @@
+#[macro_use]
+extern crate log;
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum CaptchaError {
+    /// The character at `position` (0-based) is not a decimal digit.
+    InvalidDigit { position: usize, found: char },
+}
+
+impl fmt::Display for CaptchaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CaptchaError::InvalidDigit { position, found } => {
+                write!(f, "'{}' at position {} is not a digit", found, position)
+            }
+        }
+    }
+}
+
+impl Error for CaptchaError {}
+
+/// Parses a captcha, ignoring surrounding whitespace.
+pub fn parse_digits(captcha: &str) -> Result<Vec<u32>, CaptchaError> {
+    captcha
+        .trim()
+        .chars()
+        .enumerate()
+        .map(|(position, found)| {
+            found
+                .to_digit(10)
+                .ok_or(CaptchaError::InvalidDigit { position, found })
+        })
+        .collect()
+}
+
+/// Sums every digit that matches the digit `offset` places after it, treating
+/// the sequence as circular. Offsets larger than the sequence wrap around.
+pub fn offset_sum(digits: &[u32], offset: usize) -> u32 {
+    if digits.is_empty() {
+        return 0;
+    }
+    let len = digits.len();
+    let mut sum = 0;
+    for (idx, &digit) in digits.iter().enumerate() {
+        let other = digits[(idx + offset) % len];
+        debug!("{} -> {}", digit, other);
+        if digit == other {
+            sum += digit;
+        }
+    }
+    sum
+}
+
+/// `offset_sum` for each offset, in the order given.
+pub fn offset_sums(captcha: &str, offsets: &[usize]) -> Result<Vec<u32>, CaptchaError> {
+    let digits = parse_digits(captcha)?;
+    Ok(offsets
+        .iter()
+        .map(|&offset| offset_sum(&digits, offset))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzle_examples() {
+        let next = |captcha| offset_sums(captcha, &[1]).unwrap()[0];
+        assert_eq!(next("1122"), 3);
+        assert_eq!(next("1111"), 4);
+        assert_eq!(next("1234"), 0);
+        assert_eq!(next("91212129"), 9);
+
+        let half = |captcha: &str| offset_sums(captcha, &[captcha.len() / 2]).unwrap()[0];
+        assert_eq!(half("1212"), 6);
+        assert_eq!(half("1221"), 0);
+        assert_eq!(half("123425"), 4);
+        assert_eq!(half("123123"), 12);
+        assert_eq!(half("12131415"), 4);
+    }
+
+    #[test]
+    fn test_any_offset() {
+        assert_eq!(
+            offset_sums("1231234\n", &[0, 3, 10, 7]).unwrap(),
+            vec![16, 6, 6, 16]
+        );
+        assert_eq!(offset_sums("", &[1]).unwrap(), vec![0]);
+        assert_eq!(
+            offset_sums("12a4", &[1]),
+            Err(CaptchaError::InvalidDigit {
+                position: 2,
+                found: 'a'
+            })
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Which of the puzzle's input files to run against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    Test,
+    Real,
+}
+
+impl Input {
+    /// The file to run `part` against. The two parts have different examples,
+    /// since the part 1 example has no evenly divisible pairs.
+    pub fn path(&self, part: u32) -> &'static Path {
+        match (*self, part) {
+            (Input::Test, 2) => Path::new("data/test_input_part2"),
+            (Input::Test, _) => Path::new("data/test_input"),
+            (Input::Real, _) => Path::new("data/input"),
+        }
+    }
+}
+
+impl FromStr for Input {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Input, String> {
+        match s {
+            "test" => Ok(Input::Test),
+            "real" => Ok(Input::Real),
+            _ => Err(format!("unknown input '{}', expected 'test' or 'real'", s)),
+        }
+    }
+}
+
+/// The result of one part along with how long it took.
+pub struct Timed<T> {
+    pub part: u32,
+    pub result: T,
+    pub elapsed: Duration,
+}
+
+impl<T: fmt::Display, E: fmt::Display> fmt::Display for Timed<Result<T, E>> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let millis = self.elapsed.as_secs() as f64 * 1000.0
+            + f64::from(self.elapsed.subsec_nanos()) / 1_000_000.0;
+        match self.result {
+            Ok(ref answer) => write!(
+                f,
+                "The answer to part {} is: {} ({:.3} ms)",
+                self.part, answer, millis
+            ),
+            Err(ref err) => write!(f, "Part {} failed: {} ({:.3} ms)", self.part, err, millis),
+        }
+    }
+}
+
+/// Runs each part of a puzzle against one input file, timing every part
+/// separately.
+pub struct Harness {
+    input: Input,
+}
+
+impl Harness {
+    pub fn new(input: Input) -> Harness {
+        Harness { input }
+    }
+
+    pub fn run<T, E, F>(&self, part: u32, f: F) -> Timed<Result<T, E>>
+    where
+        F: FnOnce(&Path) -> Result<T, E>,
+    {
+        let start = Instant::now();
+        let result = f(self.input.path(part));
+        Timed {
+            part,
+            result,
+            elapsed: start.elapsed(),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+extern crate csv;
+#[macro_use]
+extern crate log;
+
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+pub mod harness;
+
+#[derive(Debug)]
+pub enum ChecksumError {
+    Csv(csv::Error),
+    /// A field on `row` (1-based) is not a non-negative integer.
+    InvalidValue {
+        row: usize,
+        value: String,
+    },
+    EmptyRow {
+        row: usize,
+    },
+    NoDivisiblePair {
+        row: usize,
+    },
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ChecksumError::Csv(ref err) => write!(f, "{}", err),
+            ChecksumError::InvalidValue { row, ref value } => {
+                write!(f, "row {}: invalid value '{}'", row, value)
+            }
+            ChecksumError::EmptyRow { row } => write!(f, "row {}: no values", row),
+            ChecksumError::NoDivisiblePair { row } => {
+                write!(f, "row {}: no value evenly divides another", row)
+            }
+        }
+    }
+}
+
+impl Error for ChecksumError {}
+
+impl From<csv::Error> for ChecksumError {
+    fn from(err: csv::Error) -> ChecksumError {
+        ChecksumError::Csv(err)
+    }
+}
+
+/// Comma for `.csv` files, tab for anything else.
+pub fn delimiter_for(path: &Path) -> u8 {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => b',',
+        _ => b'\t',
+    }
+}
+
+/// Reads the spreadsheet at `path`, one row of integers per line. Rows may
+/// have different lengths.
+pub fn read_rows(path: &Path, delimiter: u8) -> Result<Vec<Vec<u32>>, ChecksumError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_path(path)?;
+    let mut rows = Vec::new();
+    for (idx, result) in rdr.records().enumerate() {
+        let record = result?;
+        debug!("{:?}", record);
+        let integers = record
+            .iter()
+            .map(|val| {
+                val.trim()
+                    .parse::<u32>()
+                    .map_err(|_| ChecksumError::InvalidValue {
+                        row: idx + 1,
+                        value: val.to_string(),
+                    })
+            })
+            .collect::<Result<Vec<u32>, ChecksumError>>()?;
+        rows.push(integers);
+    }
+    Ok(rows)
+}
+
+/// Part 1: the sum over rows of the largest value minus the smallest.
+pub fn max_min_checksum(rows: &[Vec<u32>]) -> Result<u32, ChecksumError> {
+    let mut sum = 0;
+    for (idx, row) in rows.iter().enumerate() {
+        match (row.iter().max(), row.iter().min()) {
+            (Some(max), Some(min)) => {
+                debug!("Max diff: {}", max - min);
+                sum += max - min;
+            }
+            _ => return Err(ChecksumError::EmptyRow { row: idx + 1 }),
+        }
+    }
+    Ok(sum)
+}
+
+/// The first pair `(dividend, divisor)` of values at different positions in
+/// `row` where one evenly divides the other.
+pub fn find_divisible_vals(row: &[u32]) -> Option<(u32, u32)> {
+    for (i, &dividend) in row.iter().enumerate() {
+        for (j, &divisor) in row.iter().enumerate() {
+            if i != j && divisor != 0 && dividend % divisor == 0 {
+                return Some((dividend, divisor));
+            }
+        }
+    }
+    None
+}
+
+/// Part 2: the sum over rows of the quotient of the row's evenly divisible pair.
+pub fn divisible_checksum(rows: &[Vec<u32>]) -> Result<u32, ChecksumError> {
+    let mut sum = 0;
+    for (idx, row) in rows.iter().enumerate() {
+        let (dividend, divisor) =
+            find_divisible_vals(row).ok_or(ChecksumError::NoDivisiblePair { row: idx + 1 })?;
+        debug!("{} / {}", dividend, divisor);
+        sum += dividend / divisor;
+    }
+    Ok(sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_min_checksum() {
+        let rows = vec![vec![5, 1, 9, 5], vec![7, 5, 3], vec![2, 4, 6, 8]];
+        assert_eq!(max_min_checksum(&rows).unwrap(), 18);
+        match divisible_checksum(&rows) {
+            Err(ChecksumError::NoDivisiblePair { row }) => assert_eq!(row, 2),
+            other => panic!("expected a missing pair, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_divisible_checksum() {
+        let rows = vec![vec![5, 9, 2, 8], vec![9, 4, 7, 3], vec![3, 8, 6, 5]];
+        assert_eq!(find_divisible_vals(&rows[0]), Some((8, 2)));
+        assert_eq!(divisible_checksum(&rows).unwrap(), 9);
+        assert_eq!(find_divisible_vals(&[4, 0, 7]), Some((0, 4)));
+        assert_eq!(find_divisible_vals(&[7]), None);
+    }
+}
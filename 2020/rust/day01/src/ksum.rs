@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+/// Every set of `k` distinct indices into `values` whose values sum to
+/// `target`, each as an ascending index tuple, in lexicographic order.
+pub fn k_sum(values: &[i64], k: usize, target: i64) -> Vec<Vec<usize>> {
+    let mut solutions = match k {
+        0 if target == 0 => vec![vec![]],
+        0 => vec![],
+        1 => (0..values.len())
+            .filter(|&idx| values[idx] == target)
+            .map(|idx| vec![idx])
+            .collect(),
+        2 => two_sum(values, target)
+            .into_iter()
+            .map(|(a, b)| vec![a, b])
+            .collect(),
+        _ => meet_in_the_middle(values, k, target),
+    };
+    solutions.sort();
+    solutions
+}
+
+/// Sorts the indices by value and closes in from both ends. Runs of equal
+/// values are expanded so every matching pair is returned.
+fn two_sum(values: &[i64], target: i64) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|&idx| values[idx]);
+    let value = |pos: usize| values[order[pos]];
+    let pair = |a: usize, b: usize| (order[a].min(order[b]), order[a].max(order[b]));
+
+    let mut pairs = Vec::new();
+    if order.len() < 2 {
+        return pairs;
+    }
+    let (mut lo, mut hi) = (0, order.len() - 1);
+    while lo < hi {
+        let sum = value(lo) + value(hi);
+        if sum < target {
+            lo += 1;
+        } else if sum > target {
+            hi -= 1;
+        } else if value(lo) == value(hi) {
+            // Everything from lo to hi has the same value, so any two of them match.
+            for (a, b) in (lo..=hi).tuple_combinations() {
+                pairs.push(pair(a, b));
+            }
+            break;
+        } else {
+            let lo_end = (lo..=hi).find(|&pos| value(pos) != value(lo)).unwrap();
+            let hi_start = (lo_end..=hi)
+                .rev()
+                .find(|&pos| value(pos) != value(hi))
+                .map_or(lo_end, |pos| pos + 1);
+            for (a, b) in (lo..lo_end).cartesian_product(hi_start..=hi) {
+                pairs.push(pair(a, b));
+            }
+            lo = lo_end;
+            hi = hi_start - 1;
+        }
+    }
+    pairs
+}
+
+/// Splits each tuple into its first `k / 2` indices and the rest. The sums
+/// of every possible second half are indexed up front, then each first half
+/// looks up the remainder and keeps the halves that start after it ends.
+fn meet_in_the_middle(values: &[i64], k: usize, target: i64) -> Vec<Vec<usize>> {
+    let (left_k, right_k) = (k / 2, k - k / 2);
+    let sum = |combo: &[usize]| combo.iter().map(|&idx| values[idx]).sum::<i64>();
+
+    let mut right_halves: HashMap<i64, Vec<Vec<usize>>> = HashMap::new();
+    for combo in (0..values.len()).combinations(right_k) {
+        right_halves.entry(sum(&combo)).or_default().push(combo);
+    }
+
+    let mut solutions = Vec::new();
+    for left in (0..values.len()).combinations(left_k) {
+        let last = *left.last().unwrap();
+        if let Some(rights) = right_halves.get(&(target - sum(&left))) {
+            for right in rights.iter().filter(|right| right[0] > last) {
+                solutions.push(left.iter().chain(right.iter()).cloned().collect());
+            }
+        }
+    }
+    solutions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    fn brute_force(values: &[i64], k: usize, target: i64) -> Vec<Vec<usize>> {
+        (0..values.len())
+            .combinations(k)
+            .filter(|combo| combo.iter().map(|&idx| values[idx]).sum::<i64>() == target)
+            .collect()
+    }
+
+    #[test]
+    fn solves_example() {
+        assert_eq!(k_sum(&EXAMPLE, 2, 2020), vec![vec![0, 3]]);
+        assert_eq!(k_sum(&EXAMPLE, 3, 2020), vec![vec![1, 2, 4]]);
+        assert_eq!(k_sum(&EXAMPLE, 1, 366), vec![vec![2]]);
+        assert!(k_sum(&EXAMPLE, 4, 2020).is_empty());
+    }
+
+    #[test]
+    fn returns_every_tuple() {
+        let values = [5, 1, 3, 3, 5, 1, 0, 4, 2, 3, -1, 6];
+        for k in 0..=5 {
+            for target in -1..=15 {
+                assert_eq!(
+                    k_sum(&values, k, target),
+                    brute_force(&values, k, target),
+                    "k = {}, target = {}",
+                    k,
+                    target
+                );
+            }
+        }
+    }
+}
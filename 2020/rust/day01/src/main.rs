@@ -1,32 +1,95 @@
+mod ksum;
+
+use std::env;
 use std::io::{self, Read};
-use itertools::iproduct;
-use anyhow::{Result,anyhow};
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use ksum::k_sum;
 
 fn main() -> Result<()> {
+    let args: Vec<String> = env::args().collect();
+    // day01 --bench [size] [max_k]
+    if let Some("--bench") = args.get(1).map(String::as_str) {
+        let size = match args.get(2) {
+            Some(arg) => arg.parse()?,
+            None => 2000,
+        };
+        let max_k = match args.get(3) {
+            Some(arg) => arg.parse()?,
+            None => 4,
+        };
+        bench(size, max_k);
+        return Ok(());
+    }
+
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
+    let values = parse_input(&input)?;
 
-    println!("Part 1: {}", part1(&input)?);
-    println!("Part 2: {}", part2(&input)?);
+    // day01 --solve <k> [target]
+    if let Some("--solve") = args.get(1).map(String::as_str) {
+        let k = args
+            .get(2)
+            .ok_or_else(|| anyhow!("--solve requires k"))?
+            .parse()?;
+        let target = match args.get(3) {
+            Some(arg) => arg.parse()?,
+            None => 2020,
+        };
+        for solution in k_sum(&values, k, target) {
+            let terms: Vec<String> = solution
+                .iter()
+                .map(|&idx| format!("{} (line {})", values[idx], idx + 1))
+                .collect();
+            println!("{}", terms.join(" + "));
+        }
+        return Ok(());
+    }
+
+    println!("Part 1: {}", product_of_sum(&values, 2, 2020)?);
+    println!("Part 2: {}", product_of_sum(&values, 3, 2020)?);
     Ok(())
 }
 
-fn part1(input: &str) -> Result<(i32)> {
-    let values: Vec<i32> = input.lines().map(|x| x.parse().unwrap()).collect();
-    for (value_a, value_b) in iproduct!(values.clone(), values) {
-        if value_a + value_b == 2020 {
-            return Ok(value_a * value_b);
-        }
-    }
-    Err(anyhow!("Failed to find values that sum to 2020"))
+fn parse_input(input: &str) -> Result<Vec<i64>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            line.trim()
+                .parse::<i64>()
+                .map_err(|_| anyhow!("line {}: invalid number '{}'", idx + 1, line))
+        })
+        .collect()
 }
 
-fn part2(input: &str) -> Result<(i32)> {
-    let values: Vec<i32> = input.lines().map(|x| x.parse().unwrap()).collect();
-    for (value_a, value_b, value_c) in iproduct!(values.clone(), values.clone(), values) {
-        if value_a + value_b + value_c == 2020 {
-            return Ok(value_a * value_b * value_c);
-        }
+/// The product of the first `k` entries found that sum to `target`.
+fn product_of_sum(values: &[i64], k: usize, target: i64) -> Result<i64> {
+    k_sum(values, k, target)
+        .first()
+        .map(|solution| solution.iter().map(|&idx| values[idx]).product())
+        .ok_or_else(|| anyhow!("Failed to find {} values that sum to {}", k, target))
+}
+
+/// Times the solver on `size` random values for each k up to `max_k`. The
+/// target is the sum of k random entries, so there is always a solution.
+fn bench(size: usize, max_k: usize) {
+    let mut rng = StdRng::seed_from_u64(2020);
+    let values: Vec<i64> = (0..size).map(|_| rng.gen_range(0..1_000_000)).collect();
+    println!("{} values", size);
+    for k in 2..=max_k {
+        let target = (0..k).map(|_| values[rng.gen_range(0..size)]).sum();
+        let start = Instant::now();
+        let solutions = k_sum(&values, k, target);
+        println!(
+            "\tk = {}: {:>10.3?} ({} solutions)",
+            k,
+            start.elapsed(),
+            solutions.len()
+        );
     }
-    Err(anyhow!("Failed to find values that sum to 2020"))
 }
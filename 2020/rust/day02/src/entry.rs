@@ -0,0 +1,137 @@
+use std::fmt;
+
+use regex::Regex;
+
+/// One line of the password database: two numbers and a letter whose meaning
+/// depends on the policy, followed by the password.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub line: usize,
+    pub first: u32,
+    pub second: u32,
+    pub letter: char,
+    pub password: String,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{} {}: {}",
+            self.first, self.second, self.letter, self.password
+        )
+    }
+}
+
+/// A database line that isn't `<n>-<m> <letter>: <password>`, or whose
+/// numbers don't fit in a u32. `line` counts from 1 in the input file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MalformedLine {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for MalformedLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Reads every well-formed entry of the password database, skipping blank
+/// lines, alongside every malformed line.
+pub fn read_entries(input: &str) -> (Vec<Entry>, Vec<MalformedLine>) {
+    let parse_regex = Regex::new(r"^(\d+)-(\d+) (\S): (\S+)$").unwrap();
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let line_num = idx + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |reason| MalformedLine {
+            line: line_num,
+            reason,
+        };
+        let caps = match parse_regex.captures(line) {
+            Some(caps) => caps,
+            None => {
+                errors.push(error(format!(
+                    "expected '<n>-<m> <letter>: <password>', found '{}'",
+                    line
+                )));
+                continue;
+            }
+        };
+        let number = |idx: usize| {
+            let field = &caps[idx];
+            field
+                .parse::<u32>()
+                .map_err(|_| error(format!("'{}' is too large", field)))
+        };
+        match (number(1), number(2)) {
+            (Ok(first), Ok(second)) => entries.push(Entry {
+                line: line_num,
+                first,
+                second,
+                letter: caps[3].chars().next().unwrap(),
+                password: caps[4].to_string(),
+            }),
+            (first, second) => errors.extend(first.err().into_iter().chain(second.err())),
+        }
+    }
+
+    (entries, errors)
+}
+
+/// Reads the password database, failing if any line is malformed. Every
+/// malformed line is reported, so a damaged database can be fixed in one pass.
+pub fn parse_entries(input: &str) -> Result<Vec<Entry>, Vec<MalformedLine>> {
+    match read_entries(input) {
+        (entries, errors) if errors.is_empty() => Ok(entries),
+        (_, errors) => Err(errors),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_entries() {
+        let entries = parse_entries("1-3 a: abcde\n\n2-9 c: ccccccccc\n").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].line, 3);
+        assert_eq!(entries[1].to_string(), "2-9 c: ccccccccc");
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let errors = parse_entries("1-3 a: abcde\n1-3 b cdefg\n1-99999999999 c: c\n").unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                MalformedLine {
+                    line: 2,
+                    reason: "expected '<n>-<m> <letter>: <password>', found '1-3 b cdefg'"
+                        .to_string()
+                },
+                MalformedLine {
+                    line: 3,
+                    reason: "'99999999999' is too large".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_entries_around_malformed_lines() {
+        let (entries, errors) = read_entries("1-3 a: abcde\n1-3 b cdefg\n2-9 c: ccccccccc\n");
+        assert_eq!(
+            entries.iter().map(|entry| entry.line).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 2);
+    }
+}
@@ -1,67 +1,69 @@
-use anyhow::{anyhow, Result};
-use regex::Regex;
+mod entry;
+mod policy;
+
+use std::env;
 use std::io::{self, Read};
 
+use anyhow::{anyhow, Result};
+
+use entry::{parse_entries, read_entries, Entry, MalformedLine};
+use policy::{CountRange, PasswordPolicy, PositionalXor};
+
 fn main() -> Result<()> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
 
-    println!("Part 1: {}", part1(&input)?);
-    println!("Part 2: {}", part2(&input)?);
-    Ok(())
-}
+    let policies: [&dyn PasswordPolicy; 2] = [&CountRange, &PositionalXor];
+    // day02 --audit
+    if let Some("--audit") = env::args().nth(1).as_deref() {
+        let (entries, malformed) = read_entries(&input);
+        audit(&entries, &malformed, &policies);
+        return Ok(());
+    }
 
-struct Policy {
-    min: u32,
-    max: u32,
-    letter: char,
-}
-
-fn parse(input: &str) -> Result<Vec<(Policy, String)>> {
-    let parse_regex = Regex::new(r"(\d+)-(\d+) ([a-z]): ([a-z]+)")?;
-
-    Ok(input
-        .lines()
-        .map(|x| {
-            let caps = parse_regex.captures(x).unwrap();
-            (
-                Policy {
-                    min: caps.get(1).unwrap().as_str().parse().unwrap(),
-                    max: caps.get(2).unwrap().as_str().parse().unwrap(),
-                    letter: caps.get(3).unwrap().as_str().chars().nth(0).unwrap(),
-                },
-                String::from(caps.get(4).unwrap().as_str()),
-            )
-        })
-        .collect())
-}
+    let entries = parse_entries(&input).map_err(|errors| {
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        anyhow!("Malformed password database:\n{}", messages.join("\n"))
+    })?;
 
-fn count_matching_chars(input: &String, chr: char) -> u32 {
-    input
-        .chars()
-        .map(|x| (x == chr) as u32)
-        .fold(0, |acc, x| acc + x)
+    println!("Part 1: {}", count_valid(&entries, policies[0]));
+    println!("Part 2: {}", count_valid(&entries, policies[1]));
+    Ok(())
 }
 
-fn part1(input: &str) -> Result<u32> {
-    let values = parse(input)?;
-    Ok(values
+fn count_valid(entries: &[Entry], policy: &dyn PasswordPolicy) -> usize {
+    entries
         .iter()
-        .map(|(policy, password)| {
-            let count = count_matching_chars(password, policy.letter);
-            ((count >= policy.min) && (count <= policy.max)) as u32
-        })
-        .fold(0, |acc, x| acc + x))
+        .filter(|entry| policy.is_valid(entry))
+        .count()
 }
 
-fn part2(input: &str) -> Result<u32> {
-    let values = parse(input)?;
-    Ok(values
-        .iter()
-       .map(|(policy, password)| {
-           let chars: Vec<char> = password.chars().collect();
-            ((chars[(policy.min - 1) as usize] == policy.letter)
-                ^ (chars[(policy.max - 1) as usize] == policy.letter)) as u32
-        })
-        .fold(0, |acc, x| acc + x))
+/// Lists every malformed line and every entry that fails a policy, with the
+/// policy and the reason, then how many of the parsed entries each policy
+/// accepts. Malformed lines don't stop the rest of the database being audited.
+fn audit(entries: &[Entry], malformed: &[MalformedLine], policies: &[&dyn PasswordPolicy]) {
+    for error in malformed {
+        println!("{}", error);
+    }
+    for entry in entries {
+        for policy in policies {
+            if let Err(reason) = policy.check(entry) {
+                println!(
+                    "line {}: '{}' fails {}: {}",
+                    entry.line,
+                    entry,
+                    policy.name(),
+                    reason
+                );
+            }
+        }
+    }
+    for policy in policies {
+        println!(
+            "{}: {} of {} parsed entries valid",
+            policy.name(),
+            count_valid(entries, *policy),
+            entries.len()
+        );
+    }
 }
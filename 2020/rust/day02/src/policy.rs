@@ -0,0 +1,113 @@
+use crate::entry::Entry;
+
+/// A rule that decides whether an entry's password is valid.
+pub trait PasswordPolicy {
+    fn name(&self) -> &'static str;
+
+    /// `Err` explains why the password breaks the policy.
+    fn check(&self, entry: &Entry) -> Result<(), String>;
+
+    fn is_valid(&self, entry: &Entry) -> bool {
+        self.check(entry).is_ok()
+    }
+}
+
+/// Part 1: the letter must appear between `first` and `second` times, inclusive.
+pub struct CountRange;
+
+impl PasswordPolicy for CountRange {
+    fn name(&self) -> &'static str {
+        "count range"
+    }
+
+    fn check(&self, entry: &Entry) -> Result<(), String> {
+        let count = entry
+            .password
+            .chars()
+            .filter(|&chr| chr == entry.letter)
+            .count() as u32;
+        if count >= entry.first && count <= entry.second {
+            Ok(())
+        } else {
+            Err(format!(
+                "count of '{}' is {}, expected {}..={}",
+                entry.letter, count, entry.first, entry.second
+            ))
+        }
+    }
+}
+
+/// Part 2: exactly one of the 1-based positions `first` and `second` must
+/// hold the letter.
+pub struct PositionalXor;
+
+impl PositionalXor {
+    fn letter_at(entry: &Entry, position: u32) -> Result<char, String> {
+        let len = entry.password.chars().count();
+        if position == 0 || position as usize > len {
+            return Err(format!(
+                "position {} is outside the {}-character password",
+                position, len
+            ));
+        }
+        Ok(entry.password.chars().nth(position as usize - 1).unwrap())
+    }
+}
+
+impl PasswordPolicy for PositionalXor {
+    fn name(&self) -> &'static str {
+        "positional xor"
+    }
+
+    fn check(&self, entry: &Entry) -> Result<(), String> {
+        let first = PositionalXor::letter_at(entry, entry.first)?;
+        let second = PositionalXor::letter_at(entry, entry.second)?;
+        match (first == entry.letter, second == entry.letter) {
+            (true, false) | (false, true) => Ok(()),
+            (true, true) => Err(format!(
+                "'{}' is at both positions {} and {}",
+                entry.letter, entry.first, entry.second
+            )),
+            (false, false) => Err(format!(
+                "'{}' is at neither position {} ('{}') nor {} ('{}')",
+                entry.letter, entry.first, first, entry.second, second
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::parse_entries;
+
+    const EXAMPLE: &str = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+
+    #[test]
+    fn checks_example() {
+        let entries = parse_entries(EXAMPLE).unwrap();
+        let valid = |policy: &dyn PasswordPolicy| -> Vec<bool> {
+            entries.iter().map(|entry| policy.is_valid(entry)).collect()
+        };
+        assert_eq!(valid(&CountRange), vec![true, false, true]);
+        assert_eq!(valid(&PositionalXor), vec![true, false, false]);
+        assert_eq!(
+            CountRange.check(&entries[1]).unwrap_err(),
+            "count of 'b' is 0, expected 1..=3"
+        );
+        assert_eq!(
+            PositionalXor.check(&entries[2]).unwrap_err(),
+            "'c' is at both positions 2 and 9"
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_positions() {
+        let entries = parse_entries("1-6 a: abcde\n0-2 b: bbb\n").unwrap();
+        assert_eq!(
+            PositionalXor.check(&entries[0]).unwrap_err(),
+            "position 6 is outside the 5-character password"
+        );
+        assert!(PositionalXor.check(&entries[1]).is_err());
+    }
+}
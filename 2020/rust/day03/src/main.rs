@@ -1,53 +1,61 @@
-use anyhow::{anyhow, Result};
+mod toboggan;
+
+use std::env;
 use std::io::{self, Read};
 
+use anyhow::{anyhow, Result};
+
+use toboggan::{best_slope, check_collisions, parse_from_str, render_path, sweep, TreeMap};
+
 fn main() -> Result<()> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
+    let map = parse_from_str(&input)?;
+
+    let args: Vec<String> = env::args().collect();
+    // day03 --sweep [max_down] [max_right]
+    if let Some("--sweep") = args.get(1).map(String::as_str) {
+        let max_down = match args.get(2) {
+            Some(arg) => arg.parse()?,
+            None => 2,
+        };
+        let max_right = match args.get(3) {
+            Some(arg) => arg.parse()?,
+            None => 7,
+        };
+        return report_sweep(&map, max_down, max_right);
+    }
 
-    println!("Part 1: {}", part1(&input)?);
-    println!("Part 2: {}", part2(&input)?);
+    println!("Part 1: {}", part1(&map));
+    println!("Part 2: {}", part2(&map));
     Ok(())
 }
 
-type TreeMap = Vec<Vec<bool>>;
-
-fn parse_from_str(input: &str) -> Result<TreeMap> {
-    Ok(input
-        .lines()
-        .map(|line| line.chars().map(|x| x == '#').collect())
-        .collect())
-}
-
-fn check_collisions(map: &TreeMap, slope: (usize, usize)) -> u32 {
-    let mut count = 0;
-    let mut position = (0, 0);
-
-    while position.0 < map.len() {
-        if map[position.0][position.1] {
-            count += 1;
-        }
-
-        position.0 += slope.0;
-        position.1 = (position.1 + slope.1) % map[0].len();
+/// Prints the tree count for every slope within the bounds, then the path
+/// of the slope with the fewest trees.
+fn report_sweep(map: &TreeMap, max_down: usize, max_right: usize) -> Result<()> {
+    for ((down, right), count) in sweep(map, max_down, max_right) {
+        println!("down {:>3} right {:>3}: {} trees", down, right, count);
     }
-
-    count
+    let (slope, count) = best_slope(map, max_down, max_right)
+        .ok_or_else(|| anyhow!("No slopes within the bounds"))?;
+    println!(
+        "Fewest trees: {} going down {} right {}",
+        count, slope.0, slope.1
+    );
+    print!("{}", render_path(map, slope));
+    Ok(())
 }
 
-fn part1(input: &str) -> Result<u32> {
-    let map = parse_from_str(input)?;
-    Ok(check_collisions(&map, (1, 3)))
+fn part1(map: &TreeMap) -> u32 {
+    check_collisions(map, (1, 3))
 }
 
-fn part2(input: &str) -> Result<u64> {
-    let map = parse_from_str(input)?;
-    let slopes = vec![(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
+fn part2(map: &TreeMap) -> u64 {
+    let slopes = [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
 
-    Ok(slopes
+    slopes
         .iter()
-        .map(|x| {
-            check_collisions(&map, *x) as u64
-        })
-        .fold(1 as u64, |acc, x| acc * x))
+        .map(|x| check_collisions(map, *x) as u64)
+        .product()
 }
@@ -0,0 +1,148 @@
+use anyhow::{bail, Result};
+
+/// `true` where there is a tree. The pattern repeats to the right forever.
+pub type TreeMap = Vec<Vec<bool>>;
+
+/// A slope as `(down, right)` steps per move.
+pub type Slope = (usize, usize);
+
+pub fn parse_from_str(input: &str) -> Result<TreeMap> {
+    let map: TreeMap = input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            line.chars()
+                .map(|chr| match chr {
+                    '#' => Ok(true),
+                    '.' => Ok(false),
+                    _ => bail!("line {}: unexpected '{}'", idx + 1, chr),
+                })
+                .collect()
+        })
+        .collect::<Result<_>>()?;
+    if let Some(first) = map.first() {
+        if first.is_empty() {
+            bail!("line 1: empty row");
+        }
+        if let Some(idx) = map.iter().position(|row| row.len() != first.len()) {
+            bail!(
+                "line {}: row has {} squares, expected {}",
+                idx + 1,
+                map[idx].len(),
+                first.len()
+            );
+        }
+    }
+    Ok(map)
+}
+
+/// The squares visited going from the top-left corner down `slope` until
+/// falling off the bottom, as `(row, column)` within the first tile.
+fn path(map: &TreeMap, slope: Slope) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let width = map.first().map_or(1, Vec::len);
+    (0..map.len())
+        .step_by(slope.0)
+        .enumerate()
+        .map(move |(step, row)| (row, (step * slope.1) % width))
+}
+
+pub fn check_collisions(map: &TreeMap, slope: Slope) -> u32 {
+    path(map, slope).filter(|&(row, col)| map[row][col]).count() as u32
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Tree counts for every distinct slope with `1..=max_down` down and
+/// `0..=max_right` right. Slopes are kept in lowest terms, so `(2, 2)` is
+/// skipped in favour of `(1, 1)`.
+pub fn sweep(map: &TreeMap, max_down: usize, max_right: usize) -> Vec<(Slope, u32)> {
+    let mut counts = Vec::new();
+    for down in 1..=max_down {
+        for right in 0..=max_right {
+            if gcd(down, right) == 1 {
+                let slope = (down, right);
+                counts.push((slope, check_collisions(map, slope)));
+            }
+        }
+    }
+    counts
+}
+
+/// The slope within the bounds that hits the fewest trees. Ties go to the
+/// first slope in sweep order.
+pub fn best_slope(map: &TreeMap, max_down: usize, max_right: usize) -> Option<(Slope, u32)> {
+    sweep(map, max_down, max_right)
+        .into_iter()
+        .min_by_key(|&(_, count)| count)
+}
+
+/// The map with the path for `slope` drawn over it, wrapping at the right
+/// edge: `X` where a tree is hit and `O` on open squares.
+pub fn render_path(map: &TreeMap, slope: Slope) -> String {
+    let mut grid: Vec<Vec<char>> = map
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&tree| if tree { '#' } else { '.' })
+                .collect()
+        })
+        .collect();
+    for (row, col) in path(map, slope) {
+        grid[row][col] = if map[row][col] { 'X' } else { 'O' };
+    }
+    grid.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#
+";
+
+    #[test]
+    fn sweeps_reduced_slopes() {
+        let map = parse_from_str(EXAMPLE).unwrap();
+        let counts = sweep(&map, 2, 7);
+        assert!(counts.contains(&((1, 3), 7)));
+        assert!(counts.contains(&((2, 1), 2)));
+        assert!(!counts.iter().any(|&(slope, _)| slope == (2, 2)));
+        assert_eq!(counts.len(), 8 + 4);
+        assert_eq!(best_slope(&map, 2, 7), Some(((2, 5), 0)));
+    }
+
+    #[test]
+    fn renders_path() {
+        let map = parse_from_str(EXAMPLE).unwrap();
+        let rendered = render_path(&map, (2, 1));
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "O.##.......");
+        assert_eq!(lines[1], "#...#...#..");
+        assert_eq!(lines[2], ".X....#..#.");
+        assert_eq!(lines[10], ".#..#O..#.#");
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        assert!(parse_from_str("..#\n.#\n").is_err());
+        assert!(parse_from_str("..#\n.x.\n").is_err());
+    }
+}
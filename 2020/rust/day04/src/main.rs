@@ -1,116 +1,84 @@
-#![feature(str_split_once)]
+mod schema;
 
-#[macro_use]
-extern crate lazy_static;
-
-use std::collections::{HashMap, HashSet};
+use std::env;
 use std::io::{self, Read};
-use std::iter::FromIterator;
 
 use anyhow::{anyhow, Result};
-use regex::Regex;
+use records::{records, Record};
+
+use schema::{Passport, Schema};
 
 fn main() -> Result<()> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
 
+    if env::args().any(|arg| arg == "--report") {
+        return report(&input);
+    }
+
     println!("Part 1: {}", part1(&input)?);
     println!("Part 2: {}", part2(&input)?);
     Ok(())
 }
 
-type Passport = HashMap<String, String>;
-
-fn parse_from_str(input: &str) -> Passport {
-    input
-        .lines()
-        .flat_map(|line| line.split(" "))
+fn parse_from_record(record: &Record) -> Result<Passport> {
+    record
+        .fields()
         .map(|x| {
-            let (key, value) = x.split_once(":").unwrap();
-            (String::from(key), String::from(value))
+            let (key, value) = x.split_once(':').ok_or_else(|| {
+                anyhow!(
+                    "Passport starting on line {}: field '{}' is missing a ':'",
+                    record.start_line,
+                    x
+                )
+            })?;
+            Ok((String::from(key), String::from(value)))
         })
         .collect()
 }
 
-fn validate(passport: &Passport) -> bool {
-    let required_fields = vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
-    for field in required_fields {
-        if !passport.contains_key(field) {
-            return false;
-        }
-    }
-    return true;
+/// Every passport in the input, paired with the line it starts on.
+fn get_from_input(input: &str) -> Result<Vec<(usize, Passport)>> {
+    records(input.as_bytes())
+        .map(|record| {
+            let record = record?;
+            Ok((record.start_line, parse_from_record(&record)?))
+        })
+        .collect()
 }
 
-fn bounds(passport: &Passport, key: &str, min: u32, max: u32) -> bool {
-    let value = passport.get(key).unwrap().parse::<u32>().unwrap();
-    (value >= min) && (value <= max)
+fn count_valid(input: &str, schema: &Schema) -> Result<usize> {
+    let passports = get_from_input(input)?;
+    Ok(passports
+        .iter()
+        .filter(|(_, passport)| schema.is_valid(passport))
+        .count())
 }
 
-fn validate_height(height_str: &String) -> bool {
-    lazy_static! {
-        static ref HEIGHT_RE: Regex = Regex::new(r"^([0-9]{2,3})(in|cm)$").unwrap();
-    }
-    match HEIGHT_RE.captures(height_str) {
-        Some(captures) => {
-            let height = captures.get(1).unwrap().as_str().parse::<u32>().unwrap();
-            match captures.get(2).unwrap().as_str() {
-                "cm" => (height >= 150) && (height <= 193),
-                "in" => (height >= 59) && (height <= 76),
-                _ => false,
-            }
-        }
-        None => false,
-    }
+fn part1(input: &str) -> Result<usize> {
+    count_valid(input, &Schema::presence_only())
 }
 
-fn validate2(passport: &Passport) -> bool {
-    lazy_static! {
-        static ref PASSPORT_RE: Regex = Regex::new(r"^[0-9]{9}$").unwrap();
-        static ref HAIR_RE: Regex = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
-        static ref VALID_EYES: HashSet<String> = HashSet::from_iter(
-            vec!["amb", "blu", "brn", "gry", "grn", "hzl", "oth"]
-                .iter()
-                .map(|x| String::from(*x))
-        );
-    }
-
-    if !validate(passport) {
-        return false;
-    }
-    bounds(passport, "byr", 1920, 2002)
-        && bounds(passport, "iyr", 2010, 2020)
-        && bounds(passport, "eyr", 2020, 2030)
-        && passport.get("byr").unwrap().len() == 4
-        && passport.get("iyr").unwrap().len() == 4
-        && passport.get("eyr").unwrap().len() == 4
-        && PASSPORT_RE.is_match(passport.get("pid").unwrap())
-        && HAIR_RE.is_match(passport.get("hcl").unwrap())
-        && validate_height(passport.get("hgt").unwrap())
-        && VALID_EYES.contains(passport.get("ecl").unwrap())
+fn part2(input: &str) -> Result<usize> {
+    count_valid(input, &Schema::strict())
 }
 
-fn get_from_input(input: &str) -> Vec<Passport> {
-    let mut passports = Vec::new();
-    let mut passport_lines = Vec::new();
-    for line in input.lines() {
-        if line == "" {
-            passports.push(parse_from_str(passport_lines.join(" ").as_str()));
-            passport_lines.clear();
-        } else {
-            passport_lines.push(line);
+/// Lists every passport the strict schema rejects, with a reason per field.
+fn report(input: &str) -> Result<()> {
+    let schema = Schema::strict();
+    let passports = get_from_input(input)?;
+    let mut num_rejected = 0;
+    for (start_line, passport) in passports.iter() {
+        let errors = schema.validate(passport);
+        if errors.is_empty() {
+            continue;
+        }
+        num_rejected += 1;
+        println!("Passport starting on line {} rejected:", start_line);
+        for error in errors {
+            println!("\t{}", error);
         }
     }
-    passports.push(parse_from_str(passport_lines.join(" ").as_str()));
-    passports
-}
-
-fn part1(input: &str) -> Result<u32> {
-    let passports = get_from_input(input);
-    Ok(passports.iter().map(|x| validate(x) as u32).sum())
-}
-
-fn part2(input: &str) -> Result<u32> {
-    let passports = get_from_input(input);
-    Ok(passports.iter().map(|x| validate2(x) as u32).sum())
+    println!("{} of {} passports rejected", num_rejected, passports.len());
+    Ok(())
 }
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use regex::Regex;
+
+pub type Passport = HashMap<String, String>;
+
+/// A constraint on a single field's value.
+pub enum Check {
+    /// An unsigned number within `min..=max`, written with exactly `digits` digits if given.
+    Range {
+        min: u32,
+        max: u32,
+        digits: Option<usize>,
+    },
+    /// The whole value must match the regex.
+    Pattern(Regex),
+    /// The value must be one of a fixed set of strings.
+    OneOf(&'static [&'static str]),
+    /// A number followed by a unit, with a separate `min..=max` per unit.
+    Measure(&'static [(&'static str, u32, u32)]),
+}
+
+pub struct FieldRule {
+    pub name: &'static str,
+    pub required: bool,
+    pub check: Option<Check>,
+}
+
+/// Why a single field of a passport was rejected.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.reason)
+    }
+}
+
+fn check_range(value: &str, min: u32, max: u32) -> Result<(), String> {
+    let number = value
+        .parse::<u32>()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if number < min || number > max {
+        return Err(format!("{} is not in {}..={}", number, min, max));
+    }
+    Ok(())
+}
+
+impl Check {
+    fn check(&self, value: &str) -> Result<(), String> {
+        match self {
+            Check::Range { min, max, digits } => {
+                if let Some(digits) = digits {
+                    if value.len() != *digits {
+                        return Err(format!(
+                            "'{}' has {} digits, expected {}",
+                            value,
+                            value.len(),
+                            digits
+                        ));
+                    }
+                }
+                check_range(value, *min, *max)
+            }
+            Check::Pattern(re) => {
+                if re.is_match(value) {
+                    Ok(())
+                } else {
+                    Err(format!("'{}' does not match {}", value, re.as_str()))
+                }
+            }
+            Check::OneOf(options) => {
+                if options.contains(&value) {
+                    Ok(())
+                } else {
+                    Err(format!("'{}' is not one of {}", value, options.join(", ")))
+                }
+            }
+            Check::Measure(units) => {
+                let split = value
+                    .find(|ch: char| !ch.is_ascii_digit())
+                    .unwrap_or(value.len());
+                let (number, unit) = value.split_at(split);
+                match units.iter().find(|(name, _, _)| *name == unit) {
+                    Some((_, min, max)) => check_range(number, *min, *max)
+                        .map_err(|reason| format!("{} for {}", reason, unit)),
+                    None if unit.is_empty() => Err(format!("'{}' is missing a unit", value)),
+                    None => Err(format!(
+                        "'{}' has unit '{}', expected one of {}",
+                        value,
+                        unit,
+                        units
+                            .iter()
+                            .map(|(name, _, _)| *name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )),
+                }
+            }
+        }
+    }
+}
+
+/// A declarative list of passport fields and the constraints on each.
+pub struct Schema {
+    pub fields: Vec<FieldRule>,
+}
+
+impl Schema {
+    /// Part 1: every field except `cid` must be present, with any value.
+    pub fn presence_only() -> Schema {
+        let required = |name| FieldRule {
+            name,
+            required: true,
+            check: None,
+        };
+        Schema {
+            fields: vec![
+                required("byr"),
+                required("iyr"),
+                required("eyr"),
+                required("hgt"),
+                required("hcl"),
+                required("ecl"),
+                required("pid"),
+                FieldRule {
+                    name: "cid",
+                    required: false,
+                    check: None,
+                },
+            ],
+        }
+    }
+
+    /// Part 2: the same fields, each with its value constrained.
+    pub fn strict() -> Schema {
+        let year = |min, max| {
+            Some(Check::Range {
+                min,
+                max,
+                digits: Some(4),
+            })
+        };
+        let required = |name, check| FieldRule {
+            name,
+            required: true,
+            check,
+        };
+        Schema {
+            fields: vec![
+                required("byr", year(1920, 2002)),
+                required("iyr", year(2010, 2020)),
+                required("eyr", year(2020, 2030)),
+                required(
+                    "hgt",
+                    Some(Check::Measure(&[("cm", 150, 193), ("in", 59, 76)])),
+                ),
+                required(
+                    "hcl",
+                    Some(Check::Pattern(Regex::new(r"^#[0-9a-f]{6}$").unwrap())),
+                ),
+                required(
+                    "ecl",
+                    Some(Check::OneOf(&[
+                        "amb", "blu", "brn", "gry", "grn", "hzl", "oth",
+                    ])),
+                ),
+                required(
+                    "pid",
+                    Some(Check::Pattern(Regex::new(r"^[0-9]{9}$").unwrap())),
+                ),
+                FieldRule {
+                    name: "cid",
+                    required: false,
+                    check: None,
+                },
+            ],
+        }
+    }
+
+    /// Every field that fails the schema, in schema order. Empty if the passport is valid.
+    pub fn validate(&self, passport: &Passport) -> Vec<FieldError> {
+        self.fields
+            .iter()
+            .filter_map(|rule| {
+                let reason = match (passport.get(rule.name), &rule.check) {
+                    (None, _) if rule.required => "missing".to_string(),
+                    (Some(value), Some(check)) => check.check(value).err()?,
+                    _ => return None,
+                };
+                Some(FieldError {
+                    field: rule.name,
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_valid(&self, passport: &Passport) -> bool {
+        self.validate(passport).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passport(fields: &[(&str, &str)]) -> Passport {
+        fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_every_failing_field() {
+        let schema = Schema::strict();
+        let errors = schema.validate(&passport(&[
+            ("byr", "19x0"),
+            ("iyr", "2009"),
+            ("eyr", "2030"),
+            ("hgt", "190"),
+            ("hcl", "#123abz"),
+            ("ecl", "brn"),
+        ]));
+        let fields: Vec<&str> = errors.iter().map(|e| e.field).collect();
+        assert_eq!(fields, vec!["byr", "iyr", "hgt", "hcl", "pid"]);
+        assert_eq!(errors[0].reason, "'19x0' is not a number");
+        assert_eq!(errors[1].reason, "2009 is not in 2010..=2020");
+        assert_eq!(errors[4].reason, "missing");
+    }
+
+    #[test]
+    fn unit_aware_height() {
+        let check = Check::Measure(&[("cm", 150, 193), ("in", 59, 76)]);
+        assert!(check.check("60in").is_ok());
+        assert!(check.check("190cm").is_ok());
+        assert_eq!(
+            check.check("190in").unwrap_err(),
+            "190 is not in 59..=76 for in"
+        );
+        assert!(check.check("cm").is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::{bail, Result};
+
+/// One axis of a binary space partition, written as `bits` characters where
+/// `low` keeps the lower half of the remaining range and `high` the upper.
+pub struct Dimension {
+    pub name: &'static str,
+    pub low: char,
+    pub high: char,
+    pub bits: u32,
+}
+
+/// An ordered set of dimensions. A pass is the dimensions' characters written
+/// back to back, most significant dimension first, so each character is one
+/// bit of the id. Ids are u32, so a layout holds at most `MAX_BITS` bits.
+pub struct Layout {
+    dimensions: Vec<Dimension>,
+}
+
+pub const MAX_BITS: u32 = 31;
+
+impl Layout {
+    /// Fails if the layout needs more than `MAX_BITS` bits, or if a dimension
+    /// uses the same character for both halves, which couldn't round-trip.
+    pub fn new(dimensions: Vec<Dimension>) -> Result<Layout> {
+        if let Some(dim) = dimensions.iter().find(|dim| dim.low == dim.high) {
+            bail!(
+                "Dimension '{}' uses '{}' for both halves",
+                dim.name,
+                dim.low
+            );
+        }
+        let layout = Layout { dimensions };
+        if layout.total_bits() > MAX_BITS {
+            bail!(
+                "Layout needs {} bits, but ids hold at most {}",
+                layout.total_bits(),
+                MAX_BITS
+            );
+        }
+        Ok(layout)
+    }
+
+    pub fn dimensions(&self) -> &[Dimension] {
+        &self.dimensions
+    }
+
+    /// 128 rows (`F`/`B`) of 8 seats (`L`/`R`).
+    pub fn boarding_pass() -> Layout {
+        Layout::new(vec![
+            Dimension {
+                name: "row",
+                low: 'F',
+                high: 'B',
+                bits: 7,
+            },
+            Dimension {
+                name: "column",
+                low: 'L',
+                high: 'R',
+                bits: 3,
+            },
+        ])
+        .expect("10 bits fit in an id")
+    }
+
+    pub fn total_bits(&self) -> u32 {
+        self.dimensions.iter().map(|dim| dim.bits).sum()
+    }
+
+    /// One more than the largest id the layout can encode.
+    pub fn num_ids(&self) -> u32 {
+        1 << self.total_bits()
+    }
+
+    pub fn decode(&self, pass: &str) -> Result<u32> {
+        let total_bits = self.total_bits() as usize;
+        if pass.chars().count() != total_bits {
+            bail!(
+                "Invalid boarding pass '{}': expected {} characters",
+                pass,
+                total_bits
+            );
+        }
+        let mut id = 0;
+        let mut chars = pass.chars();
+        for dim in self.dimensions.iter() {
+            for ch in chars.by_ref().take(dim.bits as usize) {
+                let bit = if ch == dim.high {
+                    1
+                } else if ch == dim.low {
+                    0
+                } else {
+                    bail!(
+                        "Invalid boarding pass '{}': '{}' is not a valid {} character",
+                        pass,
+                        ch,
+                        dim.name
+                    );
+                };
+                id = (id << 1) | bit;
+            }
+        }
+        Ok(id)
+    }
+
+    /// The value of each dimension within `id`, in layout order.
+    pub fn split_id(&self, id: u32) -> Vec<u32> {
+        let mut remaining_bits = self.total_bits();
+        self.dimensions
+            .iter()
+            .map(|dim| {
+                remaining_bits -= dim.bits;
+                (id >> remaining_bits) & ((1 << dim.bits) - 1)
+            })
+            .collect()
+    }
+
+    pub fn encode(&self, id: u32) -> Result<String> {
+        if id >= self.num_ids() {
+            bail!("Seat id {} is out of range for the layout", id);
+        }
+        let mut pass = String::with_capacity(self.total_bits() as usize);
+        for (dim, value) in self.dimensions.iter().zip(self.split_id(id)) {
+            for bit in (0..dim.bits).rev() {
+                pass.push(if (value >> bit) & 1 == 1 {
+                    dim.high
+                } else {
+                    dim.low
+                });
+            }
+        }
+        Ok(pass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_and_encodes() {
+        let layout = Layout::boarding_pass();
+        for (pass, row, col) in &[
+            ("FBFBBFFRLR", 44, 5),
+            ("BFFFBBFRRR", 70, 7),
+            ("FFFBBBFRRR", 14, 7),
+            ("BBFFBBFRLL", 102, 4),
+        ] {
+            let id = layout.decode(pass).unwrap();
+            assert_eq!(id, row * 8 + col);
+            assert_eq!(layout.split_id(id), vec![*row, *col]);
+            assert_eq!(layout.encode(id).unwrap(), *pass);
+        }
+        assert!(layout.decode("FBFBBFFRL").is_err());
+        assert!(layout.decode("FBFBBFFRLB").is_err());
+        assert!(layout.encode(1024).is_err());
+    }
+
+    #[test]
+    fn custom_layout() {
+        let layout = Layout::new(vec![
+            Dimension {
+                name: "deck",
+                low: 'D',
+                high: 'U',
+                bits: 1,
+            },
+            Dimension {
+                name: "row",
+                low: '0',
+                high: '1',
+                bits: 2,
+            },
+        ])
+        .unwrap();
+        assert_eq!(layout.decode("U10").unwrap(), 6);
+        assert_eq!(layout.encode(3).unwrap(), "D11");
+    }
+
+    #[test]
+    fn rejects_wide_layouts() {
+        let dimension = |bits| Dimension {
+            name: "row",
+            low: 'F',
+            high: 'B',
+            bits,
+        };
+        let layout = Layout::new(vec![dimension(MAX_BITS)]).unwrap();
+        assert_eq!(layout.num_ids(), 1 << 31);
+        assert_eq!(layout.decode(&"B".repeat(31)).unwrap(), (1 << 31) - 1);
+        assert!(Layout::new(vec![dimension(MAX_BITS), dimension(1)]).is_err());
+        assert!(Layout::new(vec![dimension(32)]).is_err());
+    }
+
+    #[test]
+    fn rejects_ambiguous_dimensions() {
+        let dimension = |low, high| Dimension {
+            name: "row",
+            low,
+            high,
+            bits: 7,
+        };
+        assert!(Layout::new(vec![dimension('F', 'B')]).is_ok());
+        assert!(Layout::new(vec![dimension('F', 'F')]).is_err());
+    }
+}
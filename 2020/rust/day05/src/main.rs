@@ -1,102 +1,106 @@
-#[macro_use]
-extern crate lazy_static;
+mod bsp;
+mod seat_map;
 
-use std::cmp::max;
-use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
 use std::io::{self, Read};
 
 use anyhow::{anyhow, bail, Result};
-use regex::Regex;
+
+use bsp::Layout;
+use seat_map::SeatMap;
 
 fn main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--encode") {
+        return encode(&args[1..]);
+    }
+
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
 
+    match args.first().map(String::as_str) {
+        Some("--report") => return report(&input),
+        Some("--csv") => {
+            let path = args
+                .get(1)
+                .ok_or_else(|| anyhow!("--csv requires an output path"))?;
+            return write_csv(&input, path);
+        }
+        _ => {}
+    }
+
     println!("Part 1: {}", part1(&input)?);
     println!("Part 2: {}", part2(&input)?);
     Ok(())
 }
 
-fn get_dimension(
-    boarding_pass: &str,
-    re: &Regex,
-    lower_bound_char: char,
-    upper_bound_char: char,
-    upper_bound: u32,
-) -> Result<u32> {
-    let partitioning_str = re
-        .captures(boarding_pass)
-        .ok_or(anyhow!("Invalid boarding pass - regex capture failed"))?
-        .get(0)
-        .unwrap()
-        .as_str();
-    let mut candidate_dims = (0, upper_bound);
-
-    for partition_direction in partitioning_str.chars() {
-        let distance = candidate_dims.1 - candidate_dims.0;
-        let change = (distance as f32 / 2.0).ceil() as u32;
-        if partition_direction == lower_bound_char {
-            candidate_dims.0 += change;
-        } else if partition_direction == upper_bound_char {
-            candidate_dims.1 -= change;
-        } else {
-            bail!("Invalid boarding pass - invalid row char");
-        }
-        /*
-        println!(
-            "Direction: {}, Candidates: {:?}",
-            partition_direction, candidate_dims
-        );
-         */
-    }
-    if (candidate_dims.1 - candidate_dims.0) == 0 {
-        return Ok(candidate_dims.0);
-    }
-    bail!("Not enough chars in the boarding pass");
+fn get_ids(input: &str) -> Result<Vec<u32>> {
+    let layout = Layout::boarding_pass();
+    input.lines().map(|line| layout.decode(line)).collect()
 }
 
-fn get_row(boarding_pass: &str) -> Result<u32> {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"([BF]{7})").unwrap();
-    }
-    get_dimension(boarding_pass, &RE, 'B', 'F', 127)
+fn part1(input: &str) -> Result<u32> {
+    get_ids(input)?
+        .into_iter()
+        .max()
+        .ok_or_else(|| anyhow!("No boarding passes found"))
 }
 
-fn get_col(boarding_pass: &str) -> Result<u32> {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"([LR]{3})").unwrap();
+fn part2(input: &str) -> Result<u32> {
+    let layout = Layout::boarding_pass();
+    let ids = get_ids(input)?;
+    let gaps = SeatMap::new(&layout, &ids)?.gaps();
+    match gaps.as_slice() {
+        [gap] if gap.len() == 1 => Ok(gap.start),
+        [] => bail!("No missing seats found"),
+        _ => bail!("Found too many missing seats: {:?}", gaps),
     }
-    get_dimension(boarding_pass, &RE, 'R', 'L', 7)
 }
 
-fn get_id(boarding_pass: &str) -> Result<u32> {
-    let row = get_row(boarding_pass)?;
-    let col = get_col(boarding_pass)?;
-    Ok((row * 8) + col)
+/// Prints the seat map followed by every gap between occupied seats.
+fn report(input: &str) -> Result<()> {
+    let layout = Layout::boarding_pass();
+    let ids = get_ids(input)?;
+    let seat_map = SeatMap::new(&layout, &ids)?;
+    print!("{}", seat_map.render());
+    println!();
+    for gap in seat_map.gaps() {
+        let passes = gap
+            .clone()
+            .map(|id| layout.encode(id))
+            .collect::<Result<Vec<_>>>()?;
+        println!(
+            "Gap of {} seat(s): ids {}..={} ({})",
+            gap.len(),
+            gap.start,
+            gap.end - 1,
+            passes.join(", ")
+        );
+    }
+    Ok(())
 }
 
-fn part1(input: &str) -> Result<u32> {
-    let map: Result<Vec<u32>> = input.lines().map(|line| get_id(line)).collect();
-    Ok(map?.iter().fold(0, |acc, x| max(acc, *x)))
+fn write_csv(input: &str, path: &str) -> Result<()> {
+    let layout = Layout::boarding_pass();
+    let ids = get_ids(input)?;
+    fs::write(path, SeatMap::new(&layout, &ids)?.to_csv()?)?;
+    Ok(())
 }
 
-fn part2(input: &str) -> Result<u32> {
-    let mut my_seat = Vec::new();
-    let res: Result<Vec<u32>> = input.lines().map(|line| get_id(line)).collect();
-    let mut ids = res?;
-    ids.sort_unstable();
-    for window in ids.windows(2) {
-        if (window[1] - window[0]) > 1 {
-            for x in window[0]+1..window[1] {
-                my_seat.push(x);
-            }
-        }
-    }
-    if my_seat.len() == 1 {
-        return my_seat.pop().ok_or(anyhow!("Failed to pop item"));
-    } else if my_seat.len() > 1 {
-        bail!("Found too many missing seats");
-    } else {
-        bail!("No missing seats found");
+/// Prints the boarding pass, row and column for each seat id.
+fn encode(ids: &[String]) -> Result<()> {
+    let layout = Layout::boarding_pass();
+    for id in ids {
+        let id = id.parse::<u32>()?;
+        let dims = layout.split_id(id);
+        println!(
+            "{}: {} (row {}, column {})",
+            id,
+            layout.encode(id)?,
+            dims[0],
+            dims[1]
+        );
     }
+    Ok(())
 }
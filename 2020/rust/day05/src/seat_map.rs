@@ -0,0 +1,170 @@
+use std::fmt;
+use std::ops::Range;
+
+use anyhow::{bail, Result};
+
+use crate::bsp::Layout;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeatState {
+    /// A boarding pass was scanned for this seat.
+    Occupied,
+    /// No pass was scanned, but the seat lies between occupied seats.
+    Free,
+    /// Before the first or after the last occupied seat; not on this plane.
+    Missing,
+}
+
+impl fmt::Display for SeatState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            SeatState::Occupied => "occupied",
+            SeatState::Free => "free",
+            SeatState::Missing => "missing",
+        })
+    }
+}
+
+/// The state of every seat id in a layout. The last dimension of the layout
+/// is treated as the columns and all preceding ones together as the rows.
+pub struct SeatMap<'a> {
+    layout: &'a Layout,
+    columns: u32,
+    states: Vec<SeatState>,
+}
+
+impl<'a> SeatMap<'a> {
+    pub fn new(layout: &'a Layout, ids: &[u32]) -> Result<SeatMap<'a>> {
+        let columns = match layout.dimensions().last() {
+            Some(dim) => 1 << dim.bits,
+            None => bail!("Layout has no dimensions"),
+        };
+        let mut states = vec![SeatState::Missing; layout.num_ids() as usize];
+        for &id in ids {
+            match states.get_mut(id as usize) {
+                Some(SeatState::Occupied) => {
+                    bail!("Seat id {} appears on more than one boarding pass", id)
+                }
+                Some(state) => *state = SeatState::Occupied,
+                None => bail!("Seat id {} is out of range for the layout", id),
+            }
+        }
+        if let (Some(&first), Some(&last)) = (ids.iter().min(), ids.iter().max()) {
+            for state in states[first as usize..last as usize].iter_mut() {
+                if *state == SeatState::Missing {
+                    *state = SeatState::Free;
+                }
+            }
+        }
+        Ok(SeatMap {
+            layout,
+            columns,
+            states,
+        })
+    }
+
+    /// Every run of consecutive free seat ids.
+    pub fn gaps(&self) -> Vec<Range<u32>> {
+        let mut gaps: Vec<Range<u32>> = Vec::new();
+        for (id, state) in self.states.iter().enumerate() {
+            if *state != SeatState::Free {
+                continue;
+            }
+            let id = id as u32;
+            match gaps.last_mut() {
+                Some(gap) if gap.end == id => gap.end += 1,
+                _ => gaps.push(id..id + 1),
+            }
+        }
+        gaps
+    }
+
+    /// Draws one line per row: `#` occupied, `.` free and a blank for seats
+    /// that don't exist.
+    pub fn render(&self) -> String {
+        let label_width = (self.states.len() as u32 / self.columns)
+            .saturating_sub(1)
+            .to_string()
+            .len();
+        let mut out = format!("{:>width$} ", "", width = label_width);
+        for col in 0..self.columns {
+            out.push_str(&(col % 10).to_string());
+        }
+        out.push('\n');
+        for (row, seats) in self.states.chunks(self.columns as usize).enumerate() {
+            out.push_str(&format!("{:>width$} ", row, width = label_width));
+            for state in seats {
+                out.push(match state {
+                    SeatState::Occupied => '#',
+                    SeatState::Free => '.',
+                    SeatState::Missing => ' ',
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// One line per seat id with its pass, per-dimension coordinates and state.
+    pub fn to_csv(&self) -> Result<String> {
+        let mut out = String::from("id,pass,");
+        for dim in self.layout.dimensions().iter() {
+            out.push_str(dim.name);
+            out.push(',');
+        }
+        out.push_str("state\n");
+        for (id, state) in self.states.iter().enumerate() {
+            let id = id as u32;
+            out.push_str(&format!("{},{},", id, self.layout.encode(id)?));
+            for value in self.layout.split_id(id) {
+                out.push_str(&format!("{},", value));
+            }
+            out.push_str(&format!("{}\n", state));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bsp::Dimension;
+
+    fn small_layout() -> Layout {
+        Layout::new(vec![
+            Dimension {
+                name: "row",
+                low: 'F',
+                high: 'B',
+                bits: 2,
+            },
+            Dimension {
+                name: "column",
+                low: 'L',
+                high: 'R',
+                bits: 1,
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn lists_every_gap() {
+        let layout = small_layout();
+        let seat_map = SeatMap::new(&layout, &[1, 3, 4, 6]).unwrap();
+        assert_eq!(seat_map.gaps(), vec![2..3, 5..6]);
+        assert_eq!(seat_map.render(), "  01\n0  #\n1 .#\n2 #.\n3 # \n");
+        assert!(SeatMap::new(&layout, &[1, 1]).is_err());
+    }
+
+    #[test]
+    fn exports_csv() {
+        let layout = small_layout();
+        let csv = SeatMap::new(&layout, &[1, 3]).unwrap().to_csv().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "id,pass,row,column,state");
+        assert_eq!(lines[1], "0,FFL,0,0,missing");
+        assert_eq!(lines[3], "2,FBL,1,0,free");
+        assert_eq!(lines.len(), 9);
+    }
+}
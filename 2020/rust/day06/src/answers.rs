@@ -0,0 +1,200 @@
+use std::fmt;
+
+use anyhow::{bail, Result};
+use records::Record;
+
+const QUESTIONS: u32 = 26;
+
+/// A set of questions `a` through `z`, one bit per question.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AnswerSet(u32);
+
+impl AnswerSet {
+    /// Every question, `a` through `z`.
+    pub fn all() -> AnswerSet {
+        AnswerSet((1 << QUESTIONS) - 1)
+    }
+
+    /// The questions one person answered "yes" to, e.g. `abc`. Whitespace
+    /// between answers is ignored.
+    pub fn parse(person: &str) -> Result<AnswerSet> {
+        let mut bits = 0;
+        for chr in person.chars().filter(|chr| !chr.is_whitespace()) {
+            if !chr.is_ascii_lowercase() {
+                bail!("'{}' is not a question", chr);
+            }
+            bits |= 1 << (chr as u32 - 'a' as u32);
+        }
+        Ok(AnswerSet(bits))
+    }
+
+    pub fn union(self, other: AnswerSet) -> AnswerSet {
+        AnswerSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: AnswerSet) -> AnswerSet {
+        AnswerSet(self.0 & other.0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn contains(self, question: char) -> bool {
+        question.is_ascii_lowercase() && self.0 & (1 << (question as u32 - 'a' as u32)) != 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = char> {
+        (b'a'..=b'z')
+            .map(char::from)
+            .filter(move |&question| self.contains(question))
+    }
+}
+
+impl fmt::Display for AnswerSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for question in self.iter() {
+            write!(f, "{}", question)?;
+        }
+        Ok(())
+    }
+}
+
+/// Which questions of a group to select.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Query {
+    /// Answered by anyone in the group.
+    Anyone,
+    /// Answered by everyone in the group.
+    Everyone,
+    /// Answered by exactly this many people in the group.
+    Exactly(usize),
+}
+
+impl Query {
+    pub fn parse(args: &[String]) -> Result<Query> {
+        match args {
+            [query] if query == "anyone" => Ok(Query::Anyone),
+            [query] if query == "everyone" => Ok(Query::Everyone),
+            [query, k] if query == "exactly" => Ok(Query::Exactly(k.parse()?)),
+            _ => bail!("Expected a query of 'anyone', 'everyone' or 'exactly <k>'"),
+        }
+    }
+}
+
+/// The answers of each person in one group.
+pub struct Group {
+    pub people: Vec<AnswerSet>,
+}
+
+impl Group {
+    /// One person per line of the record.
+    pub fn from_record(record: &Record) -> Result<Group> {
+        let people = record
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(idx, person)| match AnswerSet::parse(person) {
+                Ok(answers) => Ok(answers),
+                Err(err) => bail!("line {}: {}", record.start_line + idx, err),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Group { people })
+    }
+
+    pub fn query(&self, query: Query) -> AnswerSet {
+        match query {
+            Query::Anyone => self
+                .people
+                .iter()
+                .fold(AnswerSet::default(), |acc, &person| acc.union(person)),
+            Query::Everyone => self
+                .people
+                .iter()
+                .fold(AnswerSet::all(), |acc, &person| acc.intersection(person)),
+            Query::Exactly(k) => {
+                let mut bits = 0;
+                for bit in 0..QUESTIONS {
+                    let count = self
+                        .people
+                        .iter()
+                        .filter(|person| person.0 & (1 << bit) != 0)
+                        .count();
+                    if count == k {
+                        bits |= 1 << bit;
+                    }
+                }
+                AnswerSet(bits)
+            }
+        }
+    }
+}
+
+/// For each question `a` through `z`, the number of groups whose `query`
+/// selects it.
+pub fn histogram(groups: &[Group], query: Query) -> [usize; QUESTIONS as usize] {
+    let mut counts = [0; QUESTIONS as usize];
+    for group in groups {
+        for question in group.query(query).iter() {
+            counts[(question as u8 - b'a') as usize] += 1;
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use records::records;
+
+    const EXAMPLE: &str = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n";
+
+    fn groups() -> Vec<Group> {
+        records(EXAMPLE.as_bytes())
+            .map(|record| Group::from_record(&record.unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn queries_groups() {
+        let groups = groups();
+        let total = |query| -> usize { groups.iter().map(|g| g.query(query).len()).sum() };
+        assert_eq!(total(Query::Anyone), 11);
+        assert_eq!(total(Query::Everyone), 6);
+        assert_eq!(total(Query::Exactly(1)), 3 + 3 + 2 + 1);
+        assert_eq!(groups[2].query(Query::Exactly(1)).to_string(), "bc");
+        assert_eq!(groups[2].query(Query::Exactly(2)).to_string(), "a");
+    }
+
+    #[test]
+    fn builds_histogram() {
+        let counts = histogram(&groups(), Query::Everyone);
+        assert_eq!(&counts[..3], &[3, 2, 1]);
+        assert!(counts[3..].iter().all(|&count| count == 0));
+    }
+
+    #[test]
+    fn one_person_per_line() {
+        let record = records("ab cd\nac\n\nx\n y z\nq!\n".as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let group = Group::from_record(&record).unwrap();
+        assert_eq!(group.people.len(), 2);
+        assert_eq!(group.query(Query::Everyone).to_string(), "ac");
+        assert_eq!(group.query(Query::Exactly(1)).to_string(), "bd");
+
+        let record = records("ab cd\nac\n\nx\n y z\nq!\n".as_bytes())
+            .nth(1)
+            .unwrap()
+            .unwrap();
+        let err = Group::from_record(&record).err().unwrap();
+        assert_eq!(err.to_string(), "line 6: '!' is not a question");
+    }
+
+    #[test]
+    fn rejects_invalid_answers() {
+        assert!(AnswerSet::parse("abC").is_err());
+        assert_eq!(AnswerSet::parse("zza").unwrap().len(), 2);
+    }
+}
@@ -1,65 +1,64 @@
-use std::collections::{HashMap, HashSet};
+mod answers;
+
+use std::env;
 use std::io::{self, Read};
-use std::iter::FromIterator;
 
-use anyhow::{anyhow, Result};
-use itertools::Itertools;
+use anyhow::Result;
+use records::records;
+
+use answers::{histogram, Group, Query};
 
 fn main() -> Result<()> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
+    let groups = get_from_input(&input)?;
 
-    println!("Part 1: {}", part1(&input)?);
-    println!("Part 2: {}", part2(&input)?);
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        // day06 --query <anyone|everyone|exactly k>
+        Some("--query") => {
+            let query = Query::parse(&args[2..])?;
+            let total: usize = groups.iter().map(|group| group.query(query).len()).sum();
+            println!("{}", total);
+            return Ok(());
+        }
+        // day06 --histogram <anyone|everyone|exactly k>
+        Some("--histogram") => {
+            let query = Query::parse(&args[2..])?;
+            for (question, count) in (b'a'..=b'z').zip(histogram(&groups, query).iter()) {
+                println!(
+                    "{} {:>4} {}",
+                    question as char,
+                    count,
+                    "#".repeat(count / 4)
+                );
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    println!("Part 1: {}", part1(&groups));
+    println!("Part 2: {}", part2(&groups));
     Ok(())
 }
 
-fn parse_part1_from_str(lines: &Vec<&str>) -> HashSet<char> {
-    HashSet::from(
-        lines
-            .iter()
-            .flat_map(|line| line.chars())
-            .filter(|&ch| ch != ' ')
-            .collect(),
-    )
+fn get_from_input(input: &str) -> Result<Vec<Group>> {
+    records(input.as_bytes())
+        .map(|record| Group::from_record(&record?))
+        .collect()
 }
 
-fn parse_part2_from_str(lines: &Vec<&str>) -> u32 {
-    let num_people = lines.len();
-    lines
+fn part1(groups: &[Group]) -> usize {
+    groups
         .iter()
-        .flat_map(|line| line.chars())
-        .filter(|&ch| ch != ' ')
-        .sorted()
-        .group_by(|x| x.clone())
-        .into_iter()
-        .map(|(key, group)| group.into_iter().fold(0, |accum, _x| accum + 1))
-        .filter(|&x| x == num_people)
-        .fold(0, |accum, _x| accum + 1)
+        .map(|group| group.query(Query::Anyone).len())
+        .sum()
 }
 
-// TODO: replace with itertools.batching
-fn get_from_input<T>(input: &str, func: &dyn Fn(&Vec<&str>) -> T) -> Vec<T> {
-    let mut responses = Vec::new();
-    let mut response_lines = Vec::new();
-    for line in input.lines() {
-        if line == "" {
-            responses.push(func(&response_lines));
-            response_lines.clear();
-        } else {
-            response_lines.push(line);
-        }
-    }
-    responses.push(func(&response_lines));
-    responses
-}
-
-fn part1(input: &str) -> Result<usize> {
-    let responses = get_from_input(input, &parse_part1_from_str);
-    Ok(responses.iter().map(|resp| resp.len()).sum())
-}
-
-fn part2(input: &str) -> Result<u32> {
-    let responses = get_from_input(input, &parse_part2_from_str);
-    Ok(responses.iter().sum())
+fn part2(groups: &[Group]) -> usize {
+    groups
+        .iter()
+        .map(|group| group.query(Query::Everyone).len())
+        .sum()
 }
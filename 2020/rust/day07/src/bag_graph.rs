@@ -0,0 +1,191 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use petgraph::algo::toposort;
+use petgraph::prelude::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::{Direction, Graph};
+
+use crate::parser::Rule;
+
+type InternalGraph = Graph<String, u32>;
+
+/// Bag rules as a graph with edges pointing from each bag to the bags it
+/// directly contains. Construction rejects cyclic rules, so every query
+/// terminates. Query results are memoized per bag and shared across calls.
+pub struct BagGraph {
+    graph: InternalGraph,
+    name_idx_map: HashMap<String, NodeIndex>,
+    contained_memo: RefCell<HashMap<NodeIndex, u64>>,
+    containers_memo: RefCell<HashMap<NodeIndex, HashSet<NodeIndex>>>,
+}
+
+impl BagGraph {
+    fn with_capacity(nodes: usize, edges: usize) -> BagGraph {
+        BagGraph {
+            graph: Graph::<String, u32>::with_capacity(nodes, edges),
+            name_idx_map: HashMap::with_capacity(nodes),
+            contained_memo: RefCell::new(HashMap::new()),
+            containers_memo: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn insert_or_get_idx(&mut self, name: &str) -> NodeIndex {
+        match self.name_idx_map.get(name) {
+            Some(idx) => *idx,
+            None => {
+                let idx = self.graph.add_node(name.to_string());
+                self.name_idx_map.insert(name.to_string(), idx);
+                idx
+            }
+        }
+    }
+
+    /// Builds the graph from parsed rules. Every outer bag is added before any
+    /// contents so that `to_rules` emits the rules in their original order.
+    pub fn from_rules(rules: &[Rule]) -> Result<BagGraph> {
+        let num_edges = rules.iter().map(|rule| rule.contents.len()).sum();
+        let mut bag_graph = BagGraph::with_capacity(rules.len(), num_edges);
+        for rule in rules {
+            bag_graph.insert_or_get_idx(&rule.outer);
+        }
+        for rule in rules {
+            let source = bag_graph.insert_or_get_idx(&rule.outer);
+            for (count, inner) in rule.contents.iter() {
+                let dest = bag_graph.insert_or_get_idx(inner);
+                bag_graph.graph.add_edge(source, dest, *count);
+            }
+        }
+        bag_graph.check_acyclic()?;
+        Ok(bag_graph)
+    }
+
+    /// One rule per bag, in node order, with contents in the order they were added.
+    /// Bags that only ever appeared as contents get an explicit empty rule.
+    pub fn to_rules(&self) -> Vec<Rule> {
+        self.graph
+            .node_indices()
+            .map(|node| {
+                let mut edges: Vec<_> = self.graph.edges(node).collect();
+                edges.sort_by_key(|edge| edge.id());
+                Rule {
+                    outer: self.graph[node].clone(),
+                    contents: edges
+                        .iter()
+                        .map(|edge| (*edge.weight(), self.graph[edge.target()].clone()))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    fn check_acyclic(&self) -> Result<()> {
+        toposort(&self.graph, None).map(|_| ()).map_err(|cycle| {
+            anyhow!(
+                "Bag rules contain a cycle through '{}'",
+                self.graph[cycle.node_id()]
+            )
+        })
+    }
+
+    fn idx(&self, name: &str) -> Result<NodeIndex> {
+        self.name_idx_map
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown bag colour '{}'", name))
+    }
+
+    /// Total number of bags nested anywhere inside one `name` bag.
+    pub fn total_contained(&self, name: &str) -> Result<u64> {
+        Ok(self.total_contained_idx(self.idx(name)?))
+    }
+
+    fn total_contained_idx(&self, node: NodeIndex) -> u64 {
+        if let Some(count) = self.contained_memo.borrow().get(&node) {
+            return *count;
+        }
+        let count = self
+            .graph
+            .edges(node)
+            .map(|edge| *edge.weight() as u64 * (1 + self.total_contained_idx(edge.target())))
+            .sum();
+        self.contained_memo.borrow_mut().insert(node, count);
+        count
+    }
+
+    /// Every bag colour that eventually contains a `name` bag.
+    pub fn all_containers(&self, name: &str) -> Result<HashSet<String>> {
+        let containers = self.all_containers_idx(self.idx(name)?);
+        Ok(containers
+            .iter()
+            .map(|idx| self.graph[*idx].clone())
+            .collect())
+    }
+
+    fn all_containers_idx(&self, node: NodeIndex) -> HashSet<NodeIndex> {
+        if let Some(containers) = self.containers_memo.borrow().get(&node) {
+            return containers.clone();
+        }
+        let mut containers = HashSet::new();
+        for parent in self.graph.neighbors_directed(node, Direction::Incoming) {
+            containers.insert(parent);
+            containers.extend(self.all_containers_idx(parent));
+        }
+        self.containers_memo
+            .borrow_mut()
+            .insert(node, containers.clone());
+        containers
+    }
+}
+
+impl fmt::Display for BagGraph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for rule in self.to_rules() {
+            writeln!(f, "{}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_rules;
+
+    const EXAMPLE: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 3 faded blue bags.
+faded blue bags contain no other bags.
+";
+
+    fn graph_from_str(input: &str) -> Result<BagGraph> {
+        BagGraph::from_rules(&parse_rules(input).unwrap())
+    }
+
+    #[test]
+    fn queries_any_colour() {
+        let bag_graph = graph_from_str(EXAMPLE).unwrap();
+        assert_eq!(bag_graph.all_containers("shiny gold").unwrap().len(), 3);
+        assert_eq!(bag_graph.all_containers("light red").unwrap().len(), 0);
+        assert_eq!(bag_graph.total_contained("shiny gold").unwrap(), 3);
+        assert_eq!(bag_graph.total_contained("light red").unwrap(), 41);
+        assert!(bag_graph.total_contained("plaid green").is_err());
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        let res = graph_from_str(
+            "shiny gold bags contain 2 dark red bags.\ndark red bags contain 1 shiny gold bag.\n",
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn round_trips_rules() {
+        let bag_graph = graph_from_str(EXAMPLE).unwrap();
+        assert_eq!(bag_graph.to_string(), EXAMPLE);
+    }
+}
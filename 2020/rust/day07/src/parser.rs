@@ -0,0 +1,178 @@
+use std::error::Error;
+use std::fmt;
+
+/// One line of the puzzle input: an outer bag and the bags it directly contains.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    pub outer: String,
+    pub contents: Vec<(u32, String)>,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bags contain ", self.outer)?;
+        if self.contents.is_empty() {
+            return write!(f, "no other bags.");
+        }
+        for (idx, (count, colour)) in self.contents.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            let noun = if *count == 1 { "bag" } else { "bags" };
+            write!(f, "{} {} {}", count, colour, noun)?;
+        }
+        write!(f, ".")
+    }
+}
+
+/// A rule that failed to parse. Line numbers are 1-based.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses `<colour> bag[s]`, returning the colour.
+fn parse_bag(input: &str) -> Result<String, String> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    match words.split_last() {
+        Some((&"bag", colour)) | Some((&"bags", colour)) if !colour.is_empty() => {
+            Ok(colour.join(" "))
+        }
+        Some((&"bag", _)) | Some((&"bags", _)) => {
+            Err(format!("missing colour in '{}'", input.trim()))
+        }
+        _ => Err(format!(
+            "expected '<colour> bags', found '{}'",
+            input.trim()
+        )),
+    }
+}
+
+/// Parses `<count> <colour> bag[s]`.
+fn parse_content(input: &str) -> Result<(u32, String), String> {
+    let input = input.trim();
+    let (count_str, rest) = input
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| format!("expected '<count> <colour> bags', found '{}'", input))?;
+    let count = count_str
+        .parse::<u32>()
+        .map_err(|_| format!("invalid bag count '{}'", count_str))?;
+    if count == 0 {
+        return Err(format!("bag count must be positive in '{}'", input));
+    }
+    Ok((count, parse_bag(rest)?))
+}
+
+/// Parses a single rule, tolerating trailing whitespace, a missing final
+/// period, irregular spacing and singular/plural `bag`/`bags`.
+pub fn parse_rule(line: &str) -> Result<Rule, String> {
+    let line = line.trim();
+    let line = line.strip_suffix('.').unwrap_or(line);
+    let (outer_str, contents_str) = line
+        .split_once(" contain ")
+        .ok_or_else(|| "missing ' contain '".to_string())?;
+    let outer = parse_bag(outer_str)?;
+
+    let contents_words: Vec<&str> = contents_str.split_whitespace().collect();
+    let contents = match contents_words.as_slice() {
+        ["no", "other", "bags"] | ["no", "other", "bag"] => Vec::new(),
+        _ => contents_str
+            .split(',')
+            .map(parse_content)
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    Ok(Rule { outer, contents })
+}
+
+/// Parses every non-blank line, collecting all errors rather than stopping
+/// at the first. A bag colour with more than one rule is also an error.
+pub fn parse_rules(input: &str) -> Result<Vec<Rule>, Vec<ParseError>> {
+    let mut rules: Vec<(usize, Rule)> = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let line_num = idx + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_rule(line) {
+            Ok(rule) => {
+                if let Some((first_line, _)) = rules.iter().find(|(_, r)| r.outer == rule.outer) {
+                    errors.push(ParseError {
+                        line: line_num,
+                        message: format!(
+                            "duplicate rule for '{}' (first defined on line {})",
+                            rule.outer, first_line
+                        ),
+                    });
+                } else {
+                    rules.push((line_num, rule));
+                }
+            }
+            Err(message) => errors.push(ParseError {
+                line: line_num,
+                message,
+            }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(rules.into_iter().map(|(_, rule)| rule).collect())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_variations() {
+        assert_eq!(
+            parse_rule("bright white bags contain 1 shiny gold bag.  ").unwrap(),
+            Rule {
+                outer: "bright white".to_string(),
+                contents: vec![(1, "shiny gold".to_string())],
+            }
+        );
+        assert_eq!(
+            parse_rule("muted yellow bag contain 2 shiny gold bags,  9 faded blue bag").unwrap(),
+            Rule {
+                outer: "muted yellow".to_string(),
+                contents: vec![(2, "shiny gold".to_string()), (9, "faded blue".to_string())],
+            }
+        );
+        assert_eq!(
+            parse_rule("faded blue bags contain no other bags.")
+                .unwrap()
+                .contents,
+            vec![]
+        );
+    }
+
+    #[test]
+    fn collects_all_errors() {
+        let input = "light red bags contain 1 bright white bag.\n\
+                     dark orange bags hold 3 bright white bags.\n\
+                     \n\
+                     bright white bags contain x shiny gold bag.\n\
+                     light red bags contain no other bags.\n";
+        let errors = parse_rules(input).unwrap_err();
+        let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![2, 4, 5]);
+        assert_eq!(
+            errors[2].message,
+            "duplicate rule for 'light red' (first defined on line 1)"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use handheld::repair::Repair;
+use handheld::{Program, RunResult};
+use petgraph::dot::{Config, Dot};
+use petgraph::graph::{EdgeReference, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Graph;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EdgeKind {
+    Normal,
+    Repair,
+}
+
+impl fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EdgeKind::Normal => write!(f, ""),
+            EdgeKind::Repair => write!(f, "repair"),
+        }
+    }
+}
+
+/// Renders the control flow of `program` as DOT. Executed instructions are
+/// filled blue, the instructions forming the infinite loop are red, and the
+/// repaired instruction (if any) is gold with a dashed edge to its new target.
+pub fn to_dot(program: &Program, repair: Option<&Repair>) -> String {
+    let len = program.len();
+    let mut graph = Graph::<String, EdgeKind>::with_capacity(len + 1, len + 1);
+    // Node indices line up with pcs, with the final node standing in for termination.
+    for (pc, inst) in program.instructions.iter().enumerate() {
+        graph.add_node(format!("{}: {}", pc, inst));
+    }
+    graph.add_node("end".to_string());
+    for pc in 0..len {
+        let next = program.instructions[pc].next_pc(pc);
+        if next >= 0 && next <= len as i64 {
+            graph.add_edge(
+                NodeIndex::new(pc),
+                NodeIndex::new(next as usize),
+                EdgeKind::Normal,
+            );
+        }
+    }
+    if let Some(repair) = repair {
+        let next = repair.replacement.next_pc(repair.pc);
+        if next >= 0 && next <= len as i64 {
+            graph.add_edge(
+                NodeIndex::new(repair.pc),
+                NodeIndex::new(next as usize),
+                EdgeKind::Repair,
+            );
+        }
+    }
+
+    let mut unmodified = program.clone();
+    unmodified.reset();
+    let (result, path) = unmodified.run_traced();
+    let executed: HashSet<usize> = path.iter().cloned().collect();
+    let looping: HashSet<usize> = match result {
+        RunResult::InfiniteLoop { pc: loop_start, .. } => {
+            let pos = path.iter().position(|&pc| pc == loop_start).unwrap();
+            path[pos..].iter().cloned().collect()
+        }
+        _ => HashSet::new(),
+    };
+    let repaired_pc = repair.map(|r| r.pc);
+
+    let get_node_attrs = |_, (idx, _): (NodeIndex, &String)| {
+        let pc = idx.index();
+        if Some(pc) == repaired_pc {
+            "style=filled fillcolor=gold".to_string()
+        } else if looping.contains(&pc) {
+            "style=filled fillcolor=salmon".to_string()
+        } else if executed.contains(&pc) {
+            "style=filled fillcolor=lightblue".to_string()
+        } else {
+            "".to_string()
+        }
+    };
+    let get_edge_attrs = |_, edge: EdgeReference<EdgeKind>| {
+        let (src, dst) = (edge.source().index(), edge.target().index());
+        match edge.weight() {
+            EdgeKind::Repair => "style=dashed color=darkgreen".to_string(),
+            EdgeKind::Normal if looping.contains(&src) && looping.contains(&dst) => {
+                "color=red penwidth=2".to_string()
+            }
+            EdgeKind::Normal if executed.contains(&src) => "color=blue penwidth=2".to_string(),
+            EdgeKind::Normal => "".to_string(),
+        }
+    };
+
+    format!(
+        "{}",
+        Dot::with_attr_getters(
+            &graph,
+            &[Config::EdgeNoLabel],
+            &get_edge_attrs,
+            &get_node_attrs
+        )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use handheld::repair::find_repairs;
+
+    const EXAMPLE: &str =
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n";
+
+    #[test]
+    fn highlights_path_loop_and_repair() {
+        let program: Program = EXAMPLE.parse().unwrap();
+        let repairs = find_repairs(&program);
+        let dot = to_dot(&program, repairs.first());
+        let lines: Vec<&str> = dot.lines().map(str::trim).collect();
+
+        // Executed before the loop, inside the loop, and never executed.
+        assert!(lines.contains(&r#"0 [ label = "0: nop +0" style=filled fillcolor=lightblue]"#));
+        for pc in [1, 2, 3, 4, 6].iter() {
+            let prefix = format!("{} [ label = ", pc);
+            let node = lines.iter().find(|line| line.starts_with(&prefix)).unwrap();
+            assert!(node.ends_with("fillcolor=salmon]"), "{}", node);
+        }
+        assert!(lines.contains(&r#"5 [ label = "5: acc -99" ]"#));
+        assert!(lines.contains(&r#"9 [ label = "end" ]"#));
+
+        // The executed path is blue until it enters the loop, then red.
+        assert!(lines.contains(&"0 -> 1 [ color=blue penwidth=2]"));
+        assert!(lines.contains(&"7 -> 3 [ color=red penwidth=2]"));
+        assert!(lines.contains(&"8 -> 9 [ ]"));
+
+        // The repaired jmp is gold with a dashed edge to its new target.
+        assert!(lines.contains(&r#"7 [ label = "7: jmp -4" style=filled fillcolor=gold]"#));
+        assert!(lines.contains(&"7 -> 8 [ style=dashed color=darkgreen]"));
+    }
+
+    #[test]
+    fn no_repair_edge_without_repair() {
+        let program: Program = EXAMPLE.parse().unwrap();
+        let dot = to_dot(&program, None);
+        assert!(!dot.contains("dashed"));
+        assert!(dot.contains(r#"7 [ label = "7: jmp -4" style=filled fillcolor=salmon]"#));
+    }
+}
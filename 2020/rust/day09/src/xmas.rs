@@ -0,0 +1,101 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+
+/// The last `capacity` values seen, kept as a multiset so a pair sum can be
+/// checked in O(capacity) and the window slid in O(1).
+pub struct SlidingWindow {
+    capacity: usize,
+    order: VecDeque<u64>,
+    counts: HashMap<u64, usize>,
+}
+
+impl SlidingWindow {
+    pub fn new(capacity: usize) -> SlidingWindow {
+        SlidingWindow {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            counts: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Adds a value, evicting the oldest one once the window is full.
+    pub fn push(&mut self, value: u64) {
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                let count = self.counts.get_mut(&oldest).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(&oldest);
+                }
+            }
+        }
+        self.order.push_back(value);
+        *self.counts.entry(value).or_insert(0) += 1;
+    }
+
+    /// Whether two values in the window with different values sum to `target`.
+    pub fn has_pair_sum(&self, target: u64) -> bool {
+        self.counts.keys().any(|&a| {
+            a <= target && {
+                let b = target - a;
+                b != a && self.counts.contains_key(&b)
+            }
+        })
+    }
+}
+
+/// The index and value of the first number after the preamble that is not
+/// the sum of two different values among the `preamble` numbers before it.
+pub fn find_invalid(values: &[u64], preamble: usize) -> Option<(usize, u64)> {
+    let mut window = SlidingWindow::new(preamble);
+    for (idx, &value) in values.iter().enumerate() {
+        if idx >= preamble && !window.has_pair_sum(value) {
+            return Some((idx, value));
+        }
+        window.push(value);
+    }
+    None
+}
+
+/// The first run of at least two consecutive values summing to `target`,
+/// found with a two-pointer scan.
+pub fn find_contiguous_range(values: &[u64], target: u64) -> Option<Range<usize>> {
+    let mut start = 0;
+    let mut sum = 0;
+    for (end, &value) in values.iter().enumerate() {
+        sum += value;
+        while sum > target {
+            sum -= values[start];
+            start += 1;
+        }
+        if sum == target && end + 1 - start >= 2 {
+            return Some(start..end + 1);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [u64; 20] = [
+        35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
+    ];
+
+    #[test]
+    fn finds_invalid_value() {
+        assert_eq!(find_invalid(&EXAMPLE, 5), Some((14, 127)));
+        assert_eq!(find_invalid(&EXAMPLE[..14], 5), None);
+        // Equal values don't count as a pair.
+        assert_eq!(find_invalid(&[5, 5, 10], 2), Some((2, 10)));
+    }
+
+    #[test]
+    fn finds_contiguous_range() {
+        assert_eq!(find_contiguous_range(&EXAMPLE, 127), Some(2..6));
+        // A single value equal to the target doesn't count.
+        assert_eq!(find_contiguous_range(&[3, 7, 1, 2, 4], 7), Some(2..5));
+        assert_eq!(find_contiguous_range(&[3, 7, 1], 7), None);
+    }
+}
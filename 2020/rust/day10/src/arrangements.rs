@@ -0,0 +1,212 @@
+use anyhow::{anyhow, bail, Result};
+use rand::Rng;
+
+/// The joltages in an adapter chain: the outlet (0), every adapter in
+/// ascending order, and the device (`max_gap` above the largest adapter, which
+/// is 3 in the puzzle).
+pub struct Adapters {
+    pub joltages: Vec<u32>,
+    pub max_gap: u32,
+    /// `ways_to_end[i]` is the number of arrangements from joltage `i` to the device.
+    ways_to_end: Vec<u128>,
+}
+
+impl Adapters {
+    pub fn new(adapters: &[u32], max_gap: u32) -> Result<Adapters> {
+        if max_gap == 0 {
+            bail!("Maximum joltage gap must be positive");
+        }
+        let mut joltages = Vec::with_capacity(adapters.len() + 2);
+        joltages.push(0);
+        joltages.extend_from_slice(adapters);
+        joltages.sort_unstable();
+        let device = joltages
+            .last()
+            .unwrap()
+            .checked_add(max_gap)
+            .ok_or_else(|| anyhow!("Device joltage overflows u32"))?;
+        joltages.push(device);
+
+        // Bottom-up from the device: each joltage can reach the device
+        // through any of the next adapters within `max_gap` of it.
+        let mut ways_to_end = vec![0u128; joltages.len()];
+        ways_to_end[joltages.len() - 1] = 1;
+        for idx in (0..joltages.len() - 1).rev() {
+            let mut ways = 0u128;
+            for next in successors(&joltages, idx, max_gap) {
+                ways = ways
+                    .checked_add(ways_to_end[next])
+                    .ok_or_else(|| anyhow!("Arrangement count overflows u128"))?;
+            }
+            ways_to_end[idx] = ways;
+        }
+
+        Ok(Adapters {
+            joltages,
+            max_gap,
+            ways_to_end,
+        })
+    }
+
+    /// Number of distinct arrangements connecting the outlet to the device.
+    pub fn count(&self) -> u128 {
+        self.ways_to_end[0]
+    }
+
+    fn successors(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        successors(&self.joltages, idx, self.max_gap)
+            .filter(move |&next| self.ways_to_end[next] > 0)
+    }
+
+    /// Every arrangement, in lexicographic order, generated lazily.
+    pub fn iter(&self) -> Arrangements<'_> {
+        Arrangements {
+            adapters: self,
+            path: if self.count() > 0 { vec![0] } else { vec![] },
+            done: self.count() == 0,
+        }
+    }
+
+    /// A uniformly random arrangement. Each step picks its successor weighted
+    /// by the number of arrangements that continue through it.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Option<Vec<u32>> {
+        if self.count() == 0 {
+            return None;
+        }
+        let last = self.joltages.len() - 1;
+        let mut idx = 0;
+        let mut arrangement = vec![self.joltages[idx]];
+        while idx != last {
+            let mut choice = rng.gen_range(0..self.ways_to_end[idx]);
+            for next in self.successors(idx) {
+                if choice < self.ways_to_end[next] {
+                    idx = next;
+                    break;
+                }
+                choice -= self.ways_to_end[next];
+            }
+            arrangement.push(self.joltages[idx]);
+        }
+        Some(arrangement)
+    }
+}
+
+/// Indices of the joltages after `idx` that are within `max_gap` of it.
+fn successors(joltages: &[u32], idx: usize, max_gap: u32) -> impl Iterator<Item = usize> + '_ {
+    let curr = joltages[idx];
+    (idx + 1..joltages.len()).take_while(move |&next| joltages[next] - curr <= max_gap)
+}
+
+/// Depth-first walk over every arrangement, yielding each as a list of joltages.
+pub struct Arrangements<'a> {
+    adapters: &'a Adapters,
+    /// Indices into `adapters.joltages` of the arrangement being built.
+    path: Vec<usize>,
+    done: bool,
+}
+
+impl<'a> Arrangements<'a> {
+    /// Extends the current path with the first viable successor until it
+    /// reaches the device. Only viable successors are taken, so this always
+    /// succeeds.
+    fn descend(&mut self) {
+        let last = self.adapters.joltages.len() - 1;
+        while let Some(&idx) = self.path.last() {
+            if idx == last {
+                return;
+            }
+            let next = self.adapters.successors(idx).next().unwrap();
+            self.path.push(next);
+        }
+    }
+
+    /// Backtracks to the deepest point with an untried successor and takes it.
+    fn advance(&mut self) -> bool {
+        while let Some(idx) = self.path.pop() {
+            let parent = match self.path.last() {
+                Some(&parent) => parent,
+                None => return false,
+            };
+            if let Some(next) = self.adapters.successors(parent).find(|&next| next > idx) {
+                self.path.push(next);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<'a> Iterator for Arrangements<'a> {
+    type Item = Vec<u32>;
+
+    fn next(&mut self) -> Option<Vec<u32>> {
+        if self.done {
+            return None;
+        }
+        self.descend();
+        let arrangement = self
+            .path
+            .iter()
+            .map(|&idx| self.adapters.joltages[idx])
+            .collect();
+        self.done = !self.advance();
+        Some(arrangement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const SMALL: [u32; 11] = [16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
+
+    #[test]
+    fn counts_with_configurable_gap() {
+        assert_eq!(Adapters::new(&SMALL, 3).unwrap().count(), 8);
+        // SMALL has a gap of 3 between adapters 1 and 4.
+        assert_eq!(Adapters::new(&SMALL, 2).unwrap().count(), 0);
+        // The device sits `max_gap` above the last adapter, so smaller gaps
+        // still reach it.
+        let adapters = Adapters::new(&[1, 2, 3], 1).unwrap();
+        assert_eq!(adapters.joltages, vec![0, 1, 2, 3, 4]);
+        assert_eq!(adapters.count(), 1);
+        assert_eq!(Adapters::new(&[1, 2, 3], 2).unwrap().count(), 3);
+        assert_eq!(Adapters::new(&[1, 2, 3], 3).unwrap().count(), 4);
+        assert!(Adapters::new(&[u32::MAX], 3).is_err());
+        assert!(Adapters::new(&SMALL, 0).is_err());
+    }
+
+    #[test]
+    fn counts_beyond_u64() {
+        let adapters: Vec<u32> = (1..=120).collect();
+        let count = Adapters::new(&adapters, 3).unwrap().count();
+        assert!(count > u64::MAX as u128);
+    }
+
+    #[test]
+    fn enumerates_every_arrangement() {
+        let adapters = Adapters::new(&SMALL, 3).unwrap();
+        let all: Vec<Vec<u32>> = adapters.iter().collect();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[0], vec![0, 1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19, 22]);
+        assert_eq!(all[7], vec![0, 1, 4, 7, 10, 12, 15, 16, 19, 22]);
+        let mut sorted = all.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted, all);
+        assert_eq!(Adapters::new(&SMALL, 2).unwrap().iter().count(), 0);
+    }
+
+    #[test]
+    fn samples_valid_arrangements() {
+        let adapters = Adapters::new(&SMALL, 3).unwrap();
+        let all: Vec<Vec<u32>> = adapters.iter().collect();
+        let mut rng = StdRng::seed_from_u64(10);
+        for _ in 0..20 {
+            assert!(all.contains(&adapters.sample(&mut rng).unwrap()));
+        }
+    }
+}
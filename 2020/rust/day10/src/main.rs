@@ -1,66 +1,107 @@
-#[macro_use]
-extern crate cached;
+mod arrangements;
 
-use cached::UnboundCache;
+use std::env;
 use std::io::{self, Read};
 
-use anyhow::{Result};
+use anyhow::{anyhow, bail, Result};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use arrangements::Adapters;
 
 fn main() -> Result<()> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
+    let values = parse_input(&input)?;
+
+    // day10 [--max-gap N] [--list [limit] | --sample [count] [seed]]
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let max_gap = match args.iter().position(|arg| arg == "--max-gap") {
+        Some(pos) => {
+            let gap = args
+                .get(pos + 1)
+                .ok_or_else(|| anyhow!("--max-gap requires a value"))?
+                .parse()?;
+            args.drain(pos..pos + 2);
+            gap
+        }
+        None => 3,
+    };
+    match args.first().map(String::as_str) {
+        Some("--list") => {
+            let limit = match args.get(1) {
+                Some(arg) => arg.parse()?,
+                None => usize::MAX,
+            };
+            let adapters = Adapters::new(&values, max_gap)?;
+            for arrangement in adapters.iter().take(limit) {
+                println!("{}", format_arrangement(&arrangement));
+            }
+            return Ok(());
+        }
+        Some("--sample") => {
+            let count = match args.get(1) {
+                Some(arg) => arg.parse()?,
+                None => 1,
+            };
+            let mut rng = match args.get(2) {
+                Some(arg) => StdRng::seed_from_u64(arg.parse()?),
+                None => StdRng::from_entropy(),
+            };
+            let adapters = Adapters::new(&values, max_gap)?;
+            for _ in 0..count {
+                let arrangement = adapters
+                    .sample(&mut rng)
+                    .ok_or_else(|| anyhow!("No arrangement has gaps of at most {}", max_gap))?;
+                println!("{}", format_arrangement(&arrangement));
+            }
+            return Ok(());
+        }
+        Some(other) => bail!("Unknown argument '{}'", other),
+        None => {}
+    }
 
-    let part1_res = part1(&input)?;
-    println!("Part 1: {}", part1_res);
-    println!("Part 2: {}", part2(&input)?);
+    println!("Part 1: {}", part1(&values)?);
+    println!("Part 2: {}", part2(&values, max_gap)?);
     Ok(())
 }
 
-fn part1(input: &str) -> Result<u32> {
-    let mut values: Vec<u32> = input
+fn parse_input(input: &str) -> Result<Vec<u32>> {
+    input
         .lines()
-        .map(|line| line.parse::<u32>().unwrap())
-        .collect();
+        .enumerate()
+        .map(|(idx, line)| {
+            line.trim()
+                .parse::<u32>()
+                .map_err(|_| anyhow!("line {}: invalid joltage '{}'", idx + 1, line))
+        })
+        .collect()
+}
+
+fn format_arrangement(arrangement: &[u32]) -> String {
+    arrangement
+        .iter()
+        .map(|joltage| joltage.to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+fn part1(values: &[u32]) -> Result<u32> {
+    let mut values = values.to_vec();
     values.push(0);
-    values.sort();
+    values.sort_unstable();
 
     let mut diffs: Vec<u32> = values[..]
         .windows(2)
         .map(|slice| slice[1] - slice[0])
         .collect();
-    diffs.sort();
+    diffs.sort_unstable();
 
     let num_ones = diffs.iter().filter(|&&x| x == 1).count();
     let num_threes = diffs.iter().filter(|&&x| x == 3).count() + 1;
     Ok((num_ones * num_threes) as u32)
 }
 
-cached_key! {
-    CALC_WAYS: UnboundCache<String, u64> = UnboundCache::new();
-    Key = { format!("{}{}", values.len(), curr) };
-    fn calculate_ways(values: &[u32], curr: u32) -> u64 = {
-        if values.len() == 1 {
-            return 1;
-        }
-
-        let mut ret = 0;
-        let mut mut_values = values;
-        while mut_values.len() >= 2 && mut_values[0] - curr <= 3 {
-            ret += calculate_ways(&mut_values[1..], mut_values[0]);
-            mut_values = &mut_values[1..];
-        }
-        ret
-    }
-}
-
-fn part2(input: &str) -> Result<u64> {
-    let mut values: Vec<u32> = input
-        .lines()
-        .map(|line| line.parse::<u32>().unwrap())
-        .collect();
-    let last_adapter = values.iter().max().unwrap().clone();
-    values.push(last_adapter + 3);
-    values.sort();
-
-    Ok(calculate_ways(&values[..], 0))
+fn part2(values: &[u32], max_gap: u32) -> Result<u128> {
+    Ok(Adapters::new(values, max_gap)?.count())
 }
@@ -0,0 +1,193 @@
+use std::mem::swap;
+
+use crate::map::{Map, Seat, DIRECTIONS};
+
+/// Decides which seats count as a seat's neighbours.
+pub trait Neighbourhood {
+    /// Number of occupied neighbours of the seat at (row, col).
+    fn occupied_neighbours(&self, map: &Map, row: usize, col: usize) -> u8;
+}
+
+/// The eight immediately surrounding seats.
+#[derive(Clone, Copy)]
+pub struct Adjacent;
+
+impl Neighbourhood for Adjacent {
+    fn occupied_neighbours(&self, map: &Map, row: usize, col: usize) -> u8 {
+        DIRECTIONS
+            .iter()
+            .map(|(d_row, d_col)| {
+                map.is_occupied(row as i32 + d_row, col as i32 + d_col)
+                    .unwrap_or(0)
+            })
+            .sum()
+    }
+}
+
+/// The first seat visible in each of the eight directions, looking past floor.
+#[derive(Clone, Copy)]
+pub struct LineOfSight;
+
+impl Neighbourhood for LineOfSight {
+    fn occupied_neighbours(&self, map: &Map, row: usize, col: usize) -> u8 {
+        DIRECTIONS
+            .iter()
+            .map(|&dir| {
+                map.dir_is_occupied((row as i32, col as i32), dir)
+                    .unwrap_or(0)
+            })
+            .sum()
+    }
+}
+
+/// Any closure can act as a custom neighbourhood.
+impl<F> Neighbourhood for F
+where
+    F: Fn(&Map, usize, usize) -> u8,
+{
+    fn occupied_neighbours(&self, map: &Map, row: usize, col: usize) -> u8 {
+        self(map, row, col)
+    }
+}
+
+/// Computes a seat's next state from its current state and occupied neighbour count.
+pub trait TransitionRule {
+    fn next_state(&self, seat: Seat, occupied_neighbours: u8) -> Seat;
+}
+
+/// Empty seats with no occupied neighbours fill up; occupied seats with at
+/// least `crowded` occupied neighbours empty out. Floor never changes.
+pub struct SeatingRule {
+    pub crowded: u8,
+}
+
+impl TransitionRule for SeatingRule {
+    fn next_state(&self, seat: Seat, occupied_neighbours: u8) -> Seat {
+        match seat {
+            Seat::Empty if occupied_neighbours == 0 => Seat::Occupied,
+            Seat::Occupied if occupied_neighbours >= self.crowded => Seat::Empty,
+            _ => seat,
+        }
+    }
+}
+
+impl<F> TransitionRule for F
+where
+    F: Fn(Seat, u8) -> Seat,
+{
+    fn next_state(&self, seat: Seat, occupied_neighbours: u8) -> Seat {
+        self(seat, occupied_neighbours)
+    }
+}
+
+/// Steps a seat map forward, writing each generation into a second buffer
+/// and swapping them so no map is allocated per step.
+pub struct Automaton<N, R> {
+    current: Map,
+    next: Map,
+    neighbourhood: N,
+    rule: R,
+    steps: usize,
+}
+
+impl<N: Neighbourhood, R: TransitionRule> Automaton<N, R> {
+    pub fn new(map: Map, neighbourhood: N, rule: R) -> Automaton<N, R> {
+        Automaton {
+            next: map.clone(),
+            current: map,
+            neighbourhood,
+            rule,
+            steps: 0,
+        }
+    }
+
+    pub fn map(&self) -> &Map {
+        &self.current
+    }
+
+    /// Advances one generation. Returns false, leaving the step count
+    /// untouched, if nothing changed (i.e. the map has converged).
+    pub fn step(&mut self) -> bool {
+        let mut changed = false;
+        for row in 0..self.current.height {
+            for col in 0..self.current.width {
+                let seat = self.current.data[row][col];
+                let new_seat = match seat {
+                    Seat::Floor => Seat::Floor,
+                    _ => self.rule.next_state(
+                        seat,
+                        self.neighbourhood
+                            .occupied_neighbours(&self.current, row, col),
+                    ),
+                };
+                changed |= new_seat != seat;
+                self.next.data[row][col] = new_seat;
+            }
+        }
+        if changed {
+            swap(&mut self.current, &mut self.next);
+            self.steps += 1;
+        }
+        changed
+    }
+
+    /// Steps until the map stops changing and returns the number of steps taken.
+    pub fn run_to_convergence(&mut self) -> usize {
+        while self.step() {}
+        self.steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::get_map;
+
+    const EXAMPLE: &str = "L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL
+";
+
+    #[test]
+    fn converges_on_example() {
+        let mut automaton = Automaton::new(
+            get_map(EXAMPLE).unwrap(),
+            Adjacent,
+            SeatingRule { crowded: 4 },
+        );
+        assert_eq!(automaton.run_to_convergence(), 5);
+        assert_eq!(automaton.map().count_occupied(), 37);
+
+        let mut automaton = Automaton::new(
+            get_map(EXAMPLE).unwrap(),
+            LineOfSight,
+            SeatingRule { crowded: 5 },
+        );
+        assert_eq!(automaton.run_to_convergence(), 6);
+        assert_eq!(automaton.map().count_occupied(), 26);
+    }
+
+    #[test]
+    fn custom_neighbourhood_and_rule() {
+        // Every seat only looks at the seat to its right, and flips each step.
+        let right = |map: &Map, row: usize, col: usize| {
+            map.is_occupied(row as i32, col as i32 + 1).unwrap_or(0)
+        };
+        let flip = |seat: Seat, _| match seat {
+            Seat::Empty => Seat::Occupied,
+            _ => Seat::Empty,
+        };
+        let mut automaton = Automaton::new(get_map("LL\n").unwrap(), right, flip);
+        assert!(automaton.step());
+        assert_eq!(automaton.map().to_string(), "##\n");
+        assert!(automaton.step());
+        assert_eq!(automaton.map().to_string(), "LL\n");
+    }
+}
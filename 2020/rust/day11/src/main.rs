@@ -1,241 +1,197 @@
-#[macro_use]
-extern crate lazy_static;
+mod automaton;
+mod map;
+mod precomputed;
+mod render;
 
-use std::cmp::{max, min};
-use std::fmt;
+use std::env;
 use std::io::{self, Read};
-use std::mem::swap;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail, Result};
 
+use automaton::{Adjacent, Automaton, LineOfSight, Neighbourhood, SeatingRule};
+use map::{get_map, Map};
+use precomputed::{DirtySimulation, NeighbourTable};
+
 fn main() -> Result<()> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
 
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("--bench") => {
+            let iterations = match args.get(2) {
+                Some(arg) => arg.parse()?,
+                None => 20,
+            };
+            return bench(&input, iterations);
+        }
+        // day11 --animate [part] [delay_ms]
+        Some("--animate") => return visualise(&input, None, args.get(2), args.get(3)),
+        // day11 --gif <path> [part] [delay_ms]
+        Some("--gif") => {
+            let path = args
+                .get(2)
+                .ok_or_else(|| anyhow!("--gif requires an output path"))?;
+            return visualise(&input, Some(Path::new(path)), args.get(3), args.get(4));
+        }
+        _ => {}
+    }
+
     println!("Part 1: {}", part1(&input)?);
     println!("Part 2: {}", part2(&input)?);
     Ok(())
 }
 
-#[derive(Clone, PartialEq, Copy)]
-enum Seat {
-    Floor,
-    Empty,
-    Occupied,
+fn simulate<N: Neighbourhood>(map: Map, neighbourhood: N, crowded: u8) -> usize {
+    let mut automaton = Automaton::new(map, neighbourhood, SeatingRule { crowded });
+    automaton.run_to_convergence();
+    automaton.map().count_occupied()
 }
 
-impl fmt::Debug for Seat {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            Seat::Floor => ".",
-            Seat::Empty => "L",
-            Seat::Occupied => "#",
-        })
-    }
+fn simulate_dirty(map: &Map, table: NeighbourTable, crowded: u8) -> usize {
+    let mut simulation = DirtySimulation::new(map, table, SeatingRule { crowded });
+    simulation.run_to_convergence();
+    simulation.count_occupied()
 }
 
-#[derive(Clone, Debug, PartialEq)]
-struct Map {
-    data: Vec<Vec<Seat>>,
-    width: usize,
-    height: usize,
+fn part1(input: &str) -> Result<usize> {
+    let map = get_map(input)?;
+    Ok(simulate_dirty(&map, NeighbourTable::adjacent(&map), 4))
 }
 
-impl std::fmt::Display for Map {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        Ok(for row in self.data.iter() {
-            for col in row {
-                write!(f, "{:?}", col)?;
-            }
-            write!(f, "\n")?;
-        })
-    }
+fn part2(input: &str) -> Result<usize> {
+    let map = get_map(input)?;
+    Ok(simulate_dirty(&map, NeighbourTable::line_of_sight(&map), 5))
 }
 
-impl Map {
-    fn get_value(&self, row: i32, col: i32) -> Option<Seat> {
-        if col >= self.width as i32 || row >= self.height as i32 || row < 0 || col < 0 {
-            return None;
-        }
-        Some(
-            *self
-                .data
-                .get(row as usize)
-                .unwrap()
-                .get(col as usize)
-                .unwrap(),
-        )
-    }
-
-    fn is_occupied(&self, row: i32, col: i32) -> Option<u8> {
-        self.get_value(row, col)
-            .map(|x| (x == Seat::Occupied) as u8)
-    }
-
-    fn dir_is_occupied(&self, pos: (i32, i32), dir: (i32, i32)) -> Option<u8> {
-        let mut curr_pos = (pos.0 + dir.0, pos.1 + dir.1);
-        //println!("\tDirection {:?}", dir);
-        while let Some(value) = self.get_value(curr_pos.0, curr_pos.1) {
-            match value {
-                Seat::Floor => {}
-                Seat::Empty => {
-                    //println!("\t\tEmpty");
-                    return Some(0);
-                }
-                Seat::Occupied => {
-                    //println!("\t\tOccupied");
-                    return Some(1);
-                }
-            }
-            curr_pos.0 += dir.0;
-            curr_pos.1 += dir.1;
-            //println!("\tNew pos {:?}", curr_pos);
-        }
-        //println!("\t\tOut Of Bounds");
-        None
+fn run_visualisation<N: Neighbourhood>(
+    input: &str,
+    neighbourhood: N,
+    crowded: u8,
+    gif_path: Option<&Path>,
+    delay: Duration,
+) -> Result<usize> {
+    let mut automaton = Automaton::new(get_map(input)?, neighbourhood, SeatingRule { crowded });
+    match gif_path {
+        Some(path) => render::write_gif(&mut automaton, path, delay),
+        None => render::animate_terminal(&mut automaton, delay),
     }
 }
 
-fn get_map(input: &str) -> Result<Map> {
-    let mut lines = input.lines().peekable();
-    let width = lines.peek().unwrap().chars().count();
-    let mut ret = Map {
-        data: Vec::new(),
-        width,
-        height: 0,
+/// Animates the part 1 or part 2 simulation in the terminal, or writes it to
+/// `gif_path` when given.
+fn visualise(
+    input: &str,
+    gif_path: Option<&Path>,
+    part: Option<&String>,
+    delay_ms: Option<&String>,
+) -> Result<()> {
+    let delay = Duration::from_millis(match delay_ms {
+        Some(arg) => arg.parse()?,
+        None => 100,
+    });
+    let generations = match part.map(String::as_str) {
+        None | Some("1") => run_visualisation(input, Adjacent, 4, gif_path, delay)?,
+        Some("2") => run_visualisation(input, LineOfSight, 5, gif_path, delay)?,
+        Some(other) => bail!("Unknown part '{}', expected 1 or 2", other),
     };
-    for line in lines {
-        let mut row = Vec::with_capacity(width);
-        for value in line.chars() {
-            let seat = match value {
-                '.' => Seat::Floor,
-                'L' => Seat::Empty,
-                '#' => Seat::Occupied,
-                _ => bail!("Invalid seat character"),
-            };
-            row.push(seat);
-        }
-        ret.data.push(row);
-        ret.height += 1;
+    if let Some(path) = gif_path {
+        println!(
+            "Wrote {} generations to {}",
+            generations + 1,
+            path.display()
+        );
     }
-    Ok(ret)
-}
-
-fn count_neighbors1(map: &Map, row: i32, col: i32) -> u8 {
-    map.is_occupied(row - 1, col).unwrap_or(0)
-        + map.is_occupied(row + 1, col).unwrap_or(0)
-        + map.is_occupied(row - 1, col - 1).unwrap_or(0)
-        + map.is_occupied(row, col - 1).unwrap_or(0)
-        + map.is_occupied(row + 1, col - 1).unwrap_or(0)
-        + map.is_occupied(row - 1, col + 1).unwrap_or(0)
-        + map.is_occupied(row, col + 1).unwrap_or(0)
-        + map.is_occupied(row + 1, col + 1).unwrap_or(0)
-}
-
-fn count_neighbors2(map: &Map, row: i32, col: i32) -> u8 {
-    //println!("Position ({}, {})", row, col);
-    map.dir_is_occupied((row, col), (-1, 0)).unwrap_or(0)
-        + map.dir_is_occupied((row, col), (0, -1)).unwrap_or(0)
-        + map.dir_is_occupied((row, col), (0, 1)).unwrap_or(0)
-        + map.dir_is_occupied((row, col), (1, 0)).unwrap_or(0)
-        + map.dir_is_occupied((row, col), (1, -1)).unwrap_or(0)
-        + map.dir_is_occupied((row, col), (-1, 1)).unwrap_or(0)
-        + map.dir_is_occupied((row, col), (-1, -1)).unwrap_or(0)
-        + map.dir_is_occupied((row, col), (1, 1)).unwrap_or(0)
+    Ok(())
 }
 
-fn evolve1(map: &Map, new_map: &mut Map) {
-    new_map.clone_from(map);
-
-    for (row_idx, row) in map.data.iter().enumerate() {
-        for (col_idx, value) in row.iter().enumerate() {
-            if *value == Seat::Floor {
-                continue;
-            }
-            let num_neighbors = count_neighbors1(map, row_idx as i32, col_idx as i32);
-            let new_value = match num_neighbors {
-                1..=3 => continue,
-                0 => Seat::Occupied,
-                4..=8 => Seat::Empty,
-                _ => panic!("How did you get 9 neighbors on an 2D surface?"),
-            };
-
-            *new_map
-                .data
-                .get_mut(row_idx)
-                .unwrap()
-                .get_mut(col_idx)
-                .unwrap() = new_value;
-        }
+/// Average time of `run` over `iterations` runs, each given a fresh value from
+/// `setup`, which is not timed. Also returns the last run's output.
+fn time<S, T>(
+    iterations: u32,
+    mut setup: impl FnMut() -> S,
+    mut run: impl FnMut(S) -> T,
+) -> (Duration, T) {
+    let mut total = Duration::new(0, 0);
+    let mut output = None;
+    for _ in 0..iterations {
+        let state = setup();
+        let start = Instant::now();
+        output = Some(run(state));
+        total += start.elapsed();
     }
+    (total / iterations, output.expect("at least one iteration"))
 }
 
-fn evolve2(map: &Map, new_map: &mut Map) {
-    new_map.clone_from(map);
-
-    for (row_idx, row) in map.data.iter().enumerate() {
-        for (col_idx, value) in row.iter().enumerate() {
-            if *value == Seat::Floor {
-                continue;
-            }
-            let num_neighbors = count_neighbors2(map, row_idx as i32, col_idx as i32);
-            let new_value = match num_neighbors {
-                1..=4 => continue,
-                0 => Seat::Occupied,
-                5..=8 => Seat::Empty,
-                _ => panic!("How did you get 9 neighbors on an 2D surface?"),
-            };
-
-            *new_map
-                .data
-                .get_mut(row_idx)
-                .unwrap()
-                .get_mut(col_idx)
-                .unwrap() = new_value;
-        }
+fn report(name: &str, per_run: Duration, answer: Option<usize>) {
+    match answer {
+        Some(answer) => println!(
+            "\t{:<30} {:>10.3?} per run (answer {})",
+            name, per_run, answer
+        ),
+        None => println!("\t{:<30} {:>10.3?} per run", name, per_run),
     }
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let mut map = get_map(input)?;
-    let mut new_map = map.clone();
-
-    //println!("{}", new_map);
-    loop {
-        evolve1(&map, &mut new_map);
-        if map == new_map {
-            break;
-        }
-        //println!("{}", new_map);
-        swap(&mut map, &mut new_map);
-    }
-
-    Ok(map
-        .data
-        .iter()
-        .flatten()
-        .filter(|&x| *x == Seat::Occupied)
-        .count())
+fn bench_part<N: Neighbourhood + Copy>(
+    map: &Map,
+    iterations: u32,
+    scan_name: &str,
+    scan: N,
+    build_table: fn(&Map) -> NeighbourTable,
+    crowded: u8,
+) {
+    let (per_run, table) = time(iterations, || (), |()| build_table(map));
+    report("building the table", per_run, None);
+
+    let (per_run, answer) = time(
+        iterations,
+        || map.clone(),
+        |map| simulate(map, scan, crowded),
+    );
+    report(scan_name, per_run, Some(answer));
+    let (per_run, answer) = time(
+        iterations,
+        || (map.clone(), table.clone()),
+        |(map, table)| simulate(map, table, crowded),
+    );
+    report("precomputed table", per_run, Some(answer));
+    let (per_run, answer) = time(
+        iterations,
+        || table.clone(),
+        |table| simulate_dirty(map, table, crowded),
+    );
+    report("precomputed table + dirty set", per_run, Some(answer));
 }
 
-fn part2(input: &str) -> Result<usize> {
-    let mut map = get_map(input)?;
-    let mut new_map = map.clone();
-
-    //println!("{}", new_map);
-    loop {
-        evolve2(&map, &mut new_map);
-        if map == new_map {
-            break;
-        }
-        //println!("{}", new_map);
-        swap(&mut map, &mut new_map);
+/// Compares the per-generation neighbour scan against the precomputed
+/// tables, with and without the dirty set. The input is parsed once and the
+/// table built once up front; building it is timed on its own.
+fn bench(input: &str, iterations: u32) -> Result<()> {
+    if iterations == 0 {
+        bail!("--bench needs at least one iteration");
     }
-
-    Ok(map
-        .data
-        .iter()
-        .flatten()
-        .filter(|&x| *x == Seat::Occupied)
-        .count())
+    let map = get_map(input)?;
+    println!("{} iterations each", iterations);
+    println!("Part 1:");
+    bench_part(
+        &map,
+        iterations,
+        "neighbour scan",
+        Adjacent,
+        NeighbourTable::adjacent,
+        4,
+    );
+    println!("Part 2:");
+    bench_part(
+        &map,
+        iterations,
+        "ray walking",
+        LineOfSight,
+        NeighbourTable::line_of_sight,
+        5,
+    );
+    Ok(())
 }
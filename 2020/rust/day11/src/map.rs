@@ -0,0 +1,123 @@
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+#[derive(Clone, PartialEq, Copy)]
+pub enum Seat {
+    Floor,
+    Empty,
+    Occupied,
+}
+
+impl fmt::Debug for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Seat::Floor => ".",
+            Seat::Empty => "L",
+            Seat::Occupied => "#",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Map {
+    pub data: Vec<Vec<Seat>>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl std::fmt::Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for row in self.data.iter() {
+            for col in row {
+                write!(f, "{:?}", col)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The eight compass directions as (row, col) offsets.
+pub const DIRECTIONS: [(i32, i32); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+impl Map {
+    pub fn get_value(&self, row: i32, col: i32) -> Option<Seat> {
+        if col >= self.width as i32 || row >= self.height as i32 || row < 0 || col < 0 {
+            return None;
+        }
+        Some(self.data[row as usize][col as usize])
+    }
+
+    pub fn is_occupied(&self, row: i32, col: i32) -> Option<u8> {
+        self.get_value(row, col)
+            .map(|x| (x == Seat::Occupied) as u8)
+    }
+
+    pub fn dir_is_occupied(&self, pos: (i32, i32), dir: (i32, i32)) -> Option<u8> {
+        let mut curr_pos = (pos.0 + dir.0, pos.1 + dir.1);
+        while let Some(value) = self.get_value(curr_pos.0, curr_pos.1) {
+            match value {
+                Seat::Floor => {}
+                Seat::Empty => return Some(0),
+                Seat::Occupied => return Some(1),
+            }
+            curr_pos.0 += dir.0;
+            curr_pos.1 += dir.1;
+        }
+        None
+    }
+
+    pub fn count_occupied(&self) -> usize {
+        self.data
+            .iter()
+            .flatten()
+            .filter(|&x| *x == Seat::Occupied)
+            .count()
+    }
+}
+
+pub fn get_map(input: &str) -> Result<Map> {
+    let mut lines = input.lines().peekable();
+    let width = match lines.peek() {
+        Some(line) => line.chars().count(),
+        None => bail!("Empty seat map"),
+    };
+    let mut ret = Map {
+        data: Vec::new(),
+        width,
+        height: 0,
+    };
+    for line in lines {
+        let mut row = Vec::with_capacity(width);
+        for value in line.chars() {
+            let seat = match value {
+                '.' => Seat::Floor,
+                'L' => Seat::Empty,
+                '#' => Seat::Occupied,
+                _ => bail!("Invalid seat character"),
+            };
+            row.push(seat);
+        }
+        if row.len() != width {
+            bail!(
+                "Row {} has width {}, expected {}",
+                ret.height,
+                row.len(),
+                width
+            );
+        }
+        ret.data.push(row);
+        ret.height += 1;
+    }
+    Ok(ret)
+}
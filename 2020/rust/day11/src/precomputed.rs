@@ -0,0 +1,226 @@
+use crate::automaton::{Neighbourhood, TransitionRule};
+use crate::map::{Map, Seat, DIRECTIONS};
+
+/// Every seat's neighbours, resolved once up front. Floor never changes, so
+/// the table stays valid for every later generation of the same map.
+///
+/// Seats are numbered in row-major order, skipping floor, and all neighbour
+/// lists are stored back to back in one vector indexed by per-seat offsets.
+#[derive(Clone)]
+pub struct NeighbourTable {
+    width: usize,
+    seat_index: Vec<Option<u32>>,
+    positions: Vec<(usize, usize)>,
+    offsets: Vec<usize>,
+    neighbours: Vec<u32>,
+}
+
+impl NeighbourTable {
+    /// The eight immediately surrounding seats.
+    pub fn adjacent(map: &Map) -> NeighbourTable {
+        NeighbourTable::build(map, 1)
+    }
+
+    /// The first seat visible in each direction, looking past floor.
+    pub fn line_of_sight(map: &Map) -> NeighbourTable {
+        NeighbourTable::build(map, usize::MAX)
+    }
+
+    fn build(map: &Map, max_distance: usize) -> NeighbourTable {
+        let mut seat_index = vec![None; map.width * map.height];
+        let mut positions = Vec::new();
+        for (row_idx, row) in map.data.iter().enumerate() {
+            for (col_idx, seat) in row.iter().enumerate() {
+                if *seat != Seat::Floor {
+                    seat_index[row_idx * map.width + col_idx] = Some(positions.len() as u32);
+                    positions.push((row_idx, col_idx));
+                }
+            }
+        }
+
+        let mut offsets = Vec::with_capacity(positions.len() + 1);
+        let mut neighbours = Vec::with_capacity(positions.len() * DIRECTIONS.len());
+        offsets.push(0);
+        for &(row, col) in positions.iter() {
+            for (d_row, d_col) in DIRECTIONS.iter() {
+                let mut curr = (row as i32 + d_row, col as i32 + d_col);
+                let mut distance = 1;
+                while let Some(value) = map.get_value(curr.0, curr.1) {
+                    if value != Seat::Floor {
+                        let idx = curr.0 as usize * map.width + curr.1 as usize;
+                        neighbours.push(seat_index[idx].unwrap());
+                        break;
+                    }
+                    if distance == max_distance {
+                        break;
+                    }
+                    curr = (curr.0 + d_row, curr.1 + d_col);
+                    distance += 1;
+                }
+            }
+            offsets.push(neighbours.len());
+        }
+
+        NeighbourTable {
+            width: map.width,
+            seat_index,
+            positions,
+            offsets,
+            neighbours,
+        }
+    }
+
+    pub fn neighbours(&self, seat: usize) -> &[u32] {
+        &self.neighbours[self.offsets[seat]..self.offsets[seat + 1]]
+    }
+}
+
+impl Neighbourhood for NeighbourTable {
+    fn occupied_neighbours(&self, map: &Map, row: usize, col: usize) -> u8 {
+        match self.seat_index[row * self.width + col] {
+            Some(seat) => self
+                .neighbours(seat as usize)
+                .iter()
+                .filter(|&&n| {
+                    let (n_row, n_col) = self.positions[n as usize];
+                    map.data[n_row][n_col] == Seat::Occupied
+                })
+                .count() as u8,
+            None => 0,
+        }
+    }
+}
+
+/// Runs the seating simulation over a `NeighbourTable`, only re-evaluating
+/// seats that changed last step or that neighbour a seat that did.
+pub struct DirtySimulation<R> {
+    table: NeighbourTable,
+    seats: Vec<Seat>,
+    rule: R,
+    dirty: Vec<u32>,
+    is_dirty: Vec<bool>,
+    steps: usize,
+}
+
+impl<R: TransitionRule> DirtySimulation<R> {
+    pub fn new(map: &Map, table: NeighbourTable, rule: R) -> DirtySimulation<R> {
+        let seats: Vec<Seat> = table
+            .positions
+            .iter()
+            .map(|&(row, col)| map.data[row][col])
+            .collect();
+        let num_seats = seats.len();
+        DirtySimulation {
+            table,
+            seats,
+            rule,
+            dirty: (0..num_seats as u32).collect(),
+            is_dirty: vec![true; num_seats],
+            steps: 0,
+        }
+    }
+
+    fn mark_dirty(&mut self, seat: u32) {
+        if !self.is_dirty[seat as usize] {
+            self.is_dirty[seat as usize] = true;
+            self.dirty.push(seat);
+        }
+    }
+
+    /// Advances one generation. Returns false if nothing changed.
+    pub fn step(&mut self) -> bool {
+        // Decide every change before applying any, so each seat sees the previous generation.
+        let changes: Vec<(u32, Seat)> = self
+            .dirty
+            .iter()
+            .filter_map(|&seat| {
+                let current = self.seats[seat as usize];
+                let occupied = self
+                    .table
+                    .neighbours(seat as usize)
+                    .iter()
+                    .filter(|&&n| self.seats[n as usize] == Seat::Occupied)
+                    .count() as u8;
+                let next = self.rule.next_state(current, occupied);
+                if next != current {
+                    Some((seat, next))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        for seat in self.dirty.drain(..) {
+            self.is_dirty[seat as usize] = false;
+        }
+        for &(seat, next) in changes.iter() {
+            self.seats[seat as usize] = next;
+            self.mark_dirty(seat);
+            for idx in self.table.offsets[seat as usize]..self.table.offsets[seat as usize + 1] {
+                let neighbour = self.table.neighbours[idx];
+                self.mark_dirty(neighbour);
+            }
+        }
+
+        if changes.is_empty() {
+            return false;
+        }
+        self.steps += 1;
+        true
+    }
+
+    /// Steps until no seat changes and returns the number of steps taken.
+    pub fn run_to_convergence(&mut self) -> usize {
+        while self.step() {}
+        self.steps
+    }
+
+    pub fn count_occupied(&self) -> usize {
+        self.seats
+            .iter()
+            .filter(|&&seat| seat == Seat::Occupied)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::{Automaton, LineOfSight, SeatingRule};
+    use crate::map::get_map;
+
+    const EXAMPLE: &str = "L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL
+";
+
+    #[test]
+    fn line_of_sight_table() {
+        let map = get_map(".......#.\n...#.....\n.#.......\n.........\n..#L....#\n....#....\n.........\n#........\n...#.....\n").unwrap();
+        let table = NeighbourTable::line_of_sight(&map);
+        let seat = table.seat_index[4 * map.width + 3].unwrap() as usize;
+        assert_eq!(table.neighbours(seat).len(), 8);
+        assert_eq!(table.occupied_neighbours(&map, 4, 3), 8);
+    }
+
+    #[test]
+    fn matches_automaton() {
+        let map = get_map(EXAMPLE).unwrap();
+        let mut dirty = DirtySimulation::new(
+            &map,
+            NeighbourTable::line_of_sight(&map),
+            SeatingRule { crowded: 5 },
+        );
+        let mut automaton = Automaton::new(map, LineOfSight, SeatingRule { crowded: 5 });
+        assert_eq!(dirty.run_to_convergence(), automaton.run_to_convergence());
+        assert_eq!(dirty.count_occupied(), automaton.map().count_occupied());
+        assert_eq!(dirty.count_occupied(), 26);
+    }
+}
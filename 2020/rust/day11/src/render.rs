@@ -0,0 +1,219 @@
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use gif::{Encoder, Frame, Repeat};
+
+use crate::automaton::{Automaton, Neighbourhood, TransitionRule};
+use crate::map::{Map, Seat};
+
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const CURSOR_HOME: &str = "\x1b[H";
+const RESET: &str = "\x1b[0m";
+
+/// Pixels per seat along each edge of a GIF frame.
+const GIF_SCALE: usize = 4;
+
+/// Floor, empty and occupied colours, indexed by `palette_index`.
+const GIF_PALETTE: [u8; 9] = [40, 40, 40, 60, 180, 75, 220, 50, 47];
+
+fn ansi_colour(seat: Seat) -> &'static str {
+    match seat {
+        Seat::Floor => "\x1b[90m",
+        Seat::Empty => "\x1b[32m",
+        Seat::Occupied => "\x1b[1;31m",
+    }
+}
+
+fn palette_index(seat: Seat) -> u8 {
+    match seat {
+        Seat::Floor => 0,
+        Seat::Empty => 1,
+        Seat::Occupied => 2,
+    }
+}
+
+/// Renders the map with each seat coloured by state.
+pub fn ansi_frame(map: &Map) -> String {
+    let mut frame = String::with_capacity((map.width * 8 + 1) * map.height);
+    for row in map.data.iter() {
+        for seat in row.iter() {
+            frame.push_str(ansi_colour(*seat));
+            frame.push_str(&format!("{:?}", seat));
+        }
+        frame.push_str(RESET);
+        frame.push('\n');
+    }
+    frame
+}
+
+/// Redraws every generation in place until the map converges, pausing
+/// `delay` between frames. Returns the number of generations drawn after the first.
+pub fn animate_terminal<N, R>(automaton: &mut Automaton<N, R>, delay: Duration) -> Result<usize>
+where
+    N: Neighbourhood,
+    R: TransitionRule,
+{
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    write!(out, "{}", CLEAR_SCREEN)?;
+    let mut generation = 0;
+    loop {
+        write!(out, "{}{}", CURSOR_HOME, ansi_frame(automaton.map()))?;
+        writeln!(
+            out,
+            "Generation {}: {} occupied",
+            generation,
+            automaton.map().count_occupied()
+        )?;
+        out.flush()?;
+        if !automaton.step() {
+            return Ok(generation);
+        }
+        generation += 1;
+        thread::sleep(delay);
+    }
+}
+
+/// Size in pixels of a GIF frame for `map`. GIF dimensions are 16-bit.
+fn gif_size(map: &Map) -> Result<(u16, u16)> {
+    let scale = |seats: usize| {
+        seats
+            .checked_mul(GIF_SCALE)
+            .and_then(|pixels| u16::try_from(pixels).ok())
+    };
+    match (scale(map.width), scale(map.height)) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => bail!("A {}x{} map is too large for a GIF", map.width, map.height),
+    }
+}
+
+/// `delay` in the hundredths of a second GIF frames use, at most `u16::MAX`.
+fn gif_delay(delay: Duration) -> Result<u16> {
+    u16::try_from(delay.as_millis() / 10)
+        .map_err(|_| anyhow!("A delay of {:?} is too long for a GIF", delay))
+}
+
+fn gif_frame(map: &Map, delay: Duration) -> Result<Frame<'static>> {
+    let (width, height) = gif_size(map)?;
+    let delay = gif_delay(delay)?;
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for row in map.data.iter() {
+        let mut scaled_row = Vec::with_capacity(width as usize);
+        for seat in row.iter() {
+            for _ in 0..GIF_SCALE {
+                scaled_row.push(palette_index(*seat));
+            }
+        }
+        for _ in 0..GIF_SCALE {
+            pixels.extend_from_slice(&scaled_row);
+        }
+    }
+    let mut frame = Frame::from_indexed_pixels(width, height, &pixels, None);
+    frame.delay = delay;
+    Ok(frame)
+}
+
+/// Writes every generation until convergence as a looping animated GIF.
+/// Returns the number of generations written after the first, or an error
+/// before creating `path` if the map or delay doesn't fit in a GIF.
+pub fn write_gif<N, R>(
+    automaton: &mut Automaton<N, R>,
+    path: &Path,
+    delay: Duration,
+) -> Result<usize>
+where
+    N: Neighbourhood,
+    R: TransitionRule,
+{
+    let (width, height) = gif_size(automaton.map())?;
+    gif_delay(delay)?;
+    let file = File::create(path)?;
+    let mut encoder = Encoder::new(file, width, height, &GIF_PALETTE)?;
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    let mut generation = 0;
+    loop {
+        encoder.write_frame(&gif_frame(automaton.map(), delay)?)?;
+        if !automaton.step() {
+            return Ok(generation);
+        }
+        generation += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::{Adjacent, SeatingRule};
+    use crate::map::get_map;
+
+    #[test]
+    fn colours_each_seat() {
+        let map = get_map(".L#\n#.L\n").unwrap();
+        assert_eq!(
+            ansi_frame(&map),
+            "\x1b[90m.\x1b[32mL\x1b[1;31m#\x1b[0m\n\
+             \x1b[1;31m#\x1b[90m.\x1b[32mL\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn scales_gif_frame() {
+        let map = get_map(".L#\n#.L\n").unwrap();
+        let frame = gif_frame(&map, Duration::from_millis(150)).unwrap();
+        assert_eq!(frame.width as usize, 3 * GIF_SCALE);
+        assert_eq!(frame.height as usize, 2 * GIF_SCALE);
+        assert_eq!(frame.buffer.len(), 3 * 2 * GIF_SCALE * GIF_SCALE);
+        // GIF delays are in hundredths of a second.
+        assert_eq!(frame.delay, 15);
+
+        let width = frame.width as usize;
+        let pixel = |x: usize, y: usize| frame.buffer[y * width + x];
+        // Every pixel of a seat's block uses that seat's palette index.
+        for dy in 0..GIF_SCALE {
+            for dx in 0..GIF_SCALE {
+                assert_eq!(pixel(dx, dy), 0);
+                assert_eq!(pixel(GIF_SCALE + dx, dy), 1);
+                assert_eq!(pixel(2 * GIF_SCALE + dx, dy), 2);
+                assert_eq!(pixel(dx, GIF_SCALE + dy), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn writes_every_generation() {
+        let map = get_map("LL\nLL\n").unwrap();
+        let mut automaton = Automaton::new(map, Adjacent, SeatingRule { crowded: 4 });
+        let path = std::env::temp_dir().join("day11-render-test.gif");
+        // Everyone sits down, then nothing changes.
+        assert_eq!(
+            write_gif(&mut automaton, &path, Duration::from_millis(100)).unwrap(),
+            1
+        );
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&bytes[..6], b"GIF89a");
+        assert_eq!(automaton.map().count_occupied(), 4);
+    }
+
+    #[test]
+    fn rejects_what_a_gif_cannot_hold() {
+        assert_eq!(gif_delay(Duration::from_millis(655_359)).unwrap(), u16::MAX);
+        assert!(gif_delay(Duration::from_millis(655_360)).is_err());
+
+        let fits = get_map(&"L".repeat(u16::MAX as usize / GIF_SCALE)).unwrap();
+        assert!(gif_size(&fits).is_ok());
+        let too_wide = get_map(&"L".repeat(u16::MAX as usize / GIF_SCALE + 1)).unwrap();
+        assert!(gif_frame(&too_wide, Duration::from_millis(100)).is_err());
+
+        let mut automaton = Automaton::new(too_wide, Adjacent, SeatingRule { crowded: 4 });
+        let path = std::env::temp_dir().join("day11-render-too-wide.gif");
+        assert!(write_gif(&mut automaton, &path, Duration::from_millis(100)).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -1,100 +1,61 @@
-#![feature(half_open_range_patterns)]
-
 #[macro_use]
 extern crate enum_primitive_derive;
 extern crate num_traits;
 
-use num_traits::FromPrimitive;
+mod parser;
+mod route;
+mod ship;
 
+use std::env;
+use std::fs;
 use std::io::{self, Read};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+
+use parser::parse_commands;
+use route::Route;
+use ship::{execute, Action, Outcome, Ship, WaypointShip};
 
 fn main() -> Result<()> {
     let mut input = String::new();
     io::stdin().read_to_string(&mut input)?;
-
-    let part1_res = part1(&input)?;
-    println!("Part 1: {}", part1_res);
-    println!("Part 2: {}", part2(&input)?);
-    Ok(())
-}
-
-#[derive(Primitive, Clone, Debug, PartialEq, Copy)]
-enum Direction {
-    North = 0,
-    East = 1,
-    South = 2,
-    West = 3,
-}
-
-enum RelDirection {
-    Left,
-    Right,
-}
-
-struct Ship {
-    position: (i32, i32),
-    direction: Direction,
-}
-
-fn modulo(a: i32, b: i32) -> i32 {
-    ((a % b) + b) % b
-}
-
-impl Ship {
-    fn new() -> Ship {
-        Ship {
-            position: (0, 0),
-            direction: Direction::East,
-        }
-    }
-
-    fn r#move(&mut self, dir: Direction, mag: u32) {
-        match dir {
-            Direction::North => self.position.1 += mag as i32,
-            Direction::South => self.position.1 -= mag as i32,
-            Direction::East => self.position.0 += mag as i32,
-            Direction::West => self.position.0 -= mag as i32,
+    let actions = parse_commands(&input)?;
+
+    let args: Vec<String> = env::args().collect();
+    // day12 --svg <path> [part]
+    if let Some("--svg") = args.get(1).map(String::as_str) {
+        let path = args
+            .get(2)
+            .ok_or_else(|| anyhow!("--svg requires an output path"))?;
+        let route = match args.get(3).map(String::as_str) {
+            None | Some("1") => Route::trace(&mut Ship::new(), &actions)?,
+            Some("2") => Route::trace(&mut WaypointShip::new(), &actions)?,
+            Some(other) => bail!("Unknown part '{}', expected 1 or 2", other),
         };
+        fs::write(path, route.to_svg())?;
+        println!("Wrote {} positions to {}", route.positions.len(), path);
+        return Ok(());
     }
 
-    fn rotate(&mut self, dir: RelDirection, deg: u32) {
-        let num_turns = deg as i32 / 90;
-        let new_direction = modulo(
-            (self.direction as i32)
-                + match dir {
-                    RelDirection::Left => -1 * num_turns,
-                    RelDirection::Right => num_turns,
-                },
-            4,
-        );
-        self.direction = Direction::from_i32(new_direction).unwrap();
+    let (part1_res, part2_res) = (part1(&actions)?, part2(&actions)?);
+    // day12 --report
+    if let Some("--report") = args.get(1).map(String::as_str) {
+        for (name, outcome) in [("Ship", part1_res), ("Waypoint", part2_res)].iter() {
+            println!(
+                "{:<8} ended at ({}, {}), {} from the start",
+                name, outcome.position.0, outcome.position.1, outcome.distance
+            );
+        }
     }
+    println!("Part 1: {}", part1_res.distance);
+    println!("Part 2: {}", part2_res.distance);
+    Ok(())
 }
 
-fn part1(input: &str) -> Result<u32> {
-    Ok(0)
-}
-
-fn part2(input: &str) -> Result<u64> {
-    Ok(0)
+fn part1(actions: &[Action]) -> Result<Outcome> {
+    Ok(execute(&mut Ship::new(), actions)?)
 }
 
-#[cfg(test)]
-mod tests {
-    // Note this useful idiom: importing names from outer (for mod tests) scope.
-    use super::*;
-
-    #[test]
-    fn test_rotate() {
-        let mut ship = Ship::new();
-        assert_eq!(ship.direction, Direction::East);
-        ship.rotate(RelDirection::Left, 270);
-        assert_eq!(ship.direction, Direction::South);
-        ship.rotate(RelDirection::Right, 90);
-        assert_eq!(ship.direction, Direction::West);
-        ship.rotate(RelDirection::Right, 180);
-        assert_eq!(ship.direction, Direction::East);
-    }
+fn part2(actions: &[Action]) -> Result<Outcome> {
+    Ok(execute(&mut WaypointShip::new(), actions)?)
 }
@@ -0,0 +1,133 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::ship::{Action, Direction, RelDirection};
+
+/// Why a navigation command such as `R90` was rejected, with the line it
+/// appeared on, counting from 1.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    MissingAction { line: usize },
+    UnknownAction { line: usize, action: char },
+    InvalidValue { line: usize, value: String },
+    UnalignedRotation { line: usize, degrees: u32 },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingAction { line } => write!(f, "line {}: missing action", line),
+            ParseError::UnknownAction { line, action } => {
+                write!(f, "line {}: unknown action '{}'", line, action)
+            }
+            ParseError::InvalidValue { line, value } => {
+                write!(f, "line {}: invalid value '{}'", line, value)
+            }
+            ParseError::UnalignedRotation { line, degrees } => write!(
+                f,
+                "line {}: rotation of {} degrees is not a multiple of 90",
+                line, degrees
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses a single command such as `N10`, `F7` or `R90`.
+pub fn parse_command(line_num: usize, line: &str) -> Result<Action, ParseError> {
+    let line = line.trim();
+    let mut chars = line.chars();
+    let action = chars
+        .next()
+        .ok_or(ParseError::MissingAction { line: line_num })?;
+    let value_str = chars.as_str();
+    let value = value_str
+        .parse::<u32>()
+        .map_err(|_| ParseError::InvalidValue {
+            line: line_num,
+            value: value_str.to_string(),
+        })?;
+    let turn = |dir| {
+        if value % 90 == 0 {
+            Ok(Action::Turn(dir, value))
+        } else {
+            Err(ParseError::UnalignedRotation {
+                line: line_num,
+                degrees: value,
+            })
+        }
+    };
+    match action {
+        'N' => Ok(Action::Move(Direction::North, value)),
+        'S' => Ok(Action::Move(Direction::South, value)),
+        'E' => Ok(Action::Move(Direction::East, value)),
+        'W' => Ok(Action::Move(Direction::West, value)),
+        'L' => turn(RelDirection::Left),
+        'R' => turn(RelDirection::Right),
+        'F' => Ok(Action::Forward(value)),
+        _ => Err(ParseError::UnknownAction {
+            line: line_num,
+            action,
+        }),
+    }
+}
+
+/// Parses a navigation log, one command per line. Blank lines are skipped;
+/// the first bad command aborts the parse.
+pub fn parse_commands(input: &str) -> Result<Vec<Action>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| parse_command(idx + 1, line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            parse_commands("F10\nN3\n\nF7\nR90\nF11\n").unwrap(),
+            vec![
+                Action::Forward(10),
+                Action::Move(Direction::North, 3),
+                Action::Forward(7),
+                Action::Turn(RelDirection::Right, 90),
+                Action::Forward(11),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_typed_errors() {
+        assert_eq!(
+            parse_command(3, "L45"),
+            Err(ParseError::UnalignedRotation {
+                line: 3,
+                degrees: 45
+            })
+        );
+        assert_eq!(
+            parse_command(1, "X5"),
+            Err(ParseError::UnknownAction {
+                line: 1,
+                action: 'X'
+            })
+        );
+        assert_eq!(
+            parse_command(2, "F-1"),
+            Err(ParseError::InvalidValue {
+                line: 2,
+                value: "-1".to_string()
+            })
+        );
+        assert_eq!(
+            parse_commands("N1\nR\n").unwrap_err().to_string(),
+            "line 2: invalid value ''"
+        );
+    }
+}
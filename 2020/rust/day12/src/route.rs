@@ -0,0 +1,113 @@
+use std::fmt::Write;
+
+use crate::ship::{Action, Navigate, OutOfRange};
+
+/// Every position a ship occupied over a voyage, starting where it set off.
+pub struct Route {
+    pub positions: Vec<(i32, i32)>,
+}
+
+impl Route {
+    /// Applies `actions` to `ship` in order, recording its position after each.
+    pub fn trace<N: Navigate>(ship: &mut N, actions: &[Action]) -> Result<Route, OutOfRange> {
+        let mut positions = Vec::with_capacity(actions.len() + 1);
+        positions.push(ship.position());
+        for action in actions {
+            ship.apply(action)?;
+            positions.push(ship.position());
+        }
+        Ok(Route { positions })
+    }
+
+    /// Renders the route as a standalone SVG polyline, with north pointing up,
+    /// the start marked green and the end marked red.
+    pub fn to_svg(&self) -> String {
+        // Widened so that negating and spanning i32 coordinates can't overflow.
+        let xs = self.positions.iter().map(|pos| i64::from(pos.0));
+        let ys = self.positions.iter().map(|pos| -i64::from(pos.1));
+        let (min_x, max_x) = (xs.clone().min().unwrap_or(0), xs.max().unwrap_or(0));
+        let (min_y, max_y) = (ys.clone().min().unwrap_or(0), ys.max().unwrap_or(0));
+        let size = std::cmp::max(max_x - min_x, max_y - min_y).max(1);
+        let margin = size / 20 + 1;
+        let stroke = size as f64 / 500.0;
+
+        let mut path = String::new();
+        for (idx, (x, y)) in self.positions.iter().enumerate() {
+            let cmd = if idx == 0 { 'M' } else { 'L' };
+            write!(path, "{}{} {} ", cmd, x, -i64::from(*y)).unwrap();
+        }
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            min_x - margin,
+            min_y - margin,
+            max_x - min_x + 2 * margin,
+            max_y - min_y + 2 * margin
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"  <path d="{}" fill="none" stroke="black" stroke-width="{}"/>"#,
+            path.trim_end(),
+            stroke
+        )
+        .unwrap();
+        let markers = [
+            (self.positions.first(), "green"),
+            (self.positions.last(), "red"),
+        ];
+        for (pos, colour) in markers.iter() {
+            if let Some((x, y)) = pos {
+                writeln!(
+                    svg,
+                    r#"  <circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                    x,
+                    -i64::from(*y),
+                    stroke * 4.0,
+                    colour
+                )
+                .unwrap();
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ship::{Direction, RelDirection, Ship, WaypointShip};
+
+    fn example() -> Vec<Action> {
+        vec![
+            Action::Forward(10),
+            Action::Move(Direction::North, 3),
+            Action::Forward(7),
+            Action::Turn(RelDirection::Right, 90),
+            Action::Forward(11),
+        ]
+    }
+
+    #[test]
+    fn traces_both_modes() {
+        let route = Route::trace(&mut Ship::new(), &example()).unwrap();
+        assert_eq!(
+            route.positions,
+            vec![(0, 0), (10, 0), (10, 3), (17, 3), (17, 3), (17, -8)]
+        );
+        let route = Route::trace(&mut WaypointShip::new(), &example()).unwrap();
+        assert_eq!(route.positions.last(), Some(&(214, -72)));
+        assert_eq!(route.positions.len(), 6);
+    }
+
+    #[test]
+    fn svg_flips_north_up() {
+        let route = Route::trace(&mut Ship::new(), &example()).unwrap();
+        let svg = route.to_svg();
+        assert!(svg.contains(r#"d="M0 0 L10 0 L10 -3 L17 -3 L17 -3 L17 8""#));
+        assert!(svg.starts_with("<svg"));
+    }
+}
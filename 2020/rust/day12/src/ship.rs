@@ -0,0 +1,282 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use num_traits::FromPrimitive;
+
+#[derive(Primitive, Clone, Debug, PartialEq, Copy)]
+pub enum Direction {
+    North = 0,
+    East = 1,
+    South = 2,
+    West = 3,
+}
+
+impl Direction {
+    /// One unit of travel in this direction.
+    fn unit(self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, 1),
+            Direction::South => (0, -1),
+            Direction::East => (1, 0),
+            Direction::West => (-1, 0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum RelDirection {
+    Left,
+    Right,
+}
+
+/// A single navigation instruction.
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum Action {
+    Move(Direction, u32),
+    Turn(RelDirection, u32),
+    Forward(u32),
+}
+
+/// An action that would take a ship, or its waypoint, beyond what an `i32`
+/// coordinate can hold.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OutOfRange {
+    pub action: Action,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} takes the ship out of range", self.action)
+    }
+}
+
+impl Error for OutOfRange {}
+
+/// Something that can follow navigation instructions.
+pub trait Navigate {
+    fn apply(&mut self, action: &Action) -> Result<(), OutOfRange>;
+    fn position(&self) -> (i32, i32);
+}
+
+/// Where a ship ended up after a list of actions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outcome {
+    pub position: (i32, i32),
+    /// Manhattan distance from where the ship started.
+    pub distance: u64,
+}
+
+/// Applies `actions` to `ship` in order, stopping at the first one that
+/// would take it out of range.
+pub fn execute<N: Navigate>(ship: &mut N, actions: &[Action]) -> Result<Outcome, OutOfRange> {
+    let (start_x, start_y) = ship.position();
+    for action in actions {
+        ship.apply(action)?;
+    }
+    let (x, y) = ship.position();
+    Ok(Outcome {
+        position: (x, y),
+        distance: (i64::from(x) - i64::from(start_x)).unsigned_abs()
+            + (i64::from(y) - i64::from(start_y)).unsigned_abs(),
+    })
+}
+
+/// `pos` moved `times` steps of `step`, or `None` if that leaves `i32`.
+fn translate(pos: (i32, i32), step: (i32, i32), times: u32) -> Option<(i32, i32)> {
+    let times = i32::try_from(times).ok()?;
+    Some((
+        pos.0.checked_add(step.0.checked_mul(times)?)?,
+        pos.1.checked_add(step.1.checked_mul(times)?)?,
+    ))
+}
+
+pub struct Ship {
+    pub position: (i32, i32),
+    pub direction: Direction,
+}
+
+/// Clockwise quarter turns for a rotation, in `0..4`.
+fn quarter_turns(dir: RelDirection, deg: u32) -> u32 {
+    let num_turns = deg / 90 % 4;
+    match dir {
+        RelDirection::Left => (4 - num_turns) % 4,
+        RelDirection::Right => num_turns,
+    }
+}
+
+impl Ship {
+    pub fn new() -> Ship {
+        Ship {
+            position: (0, 0),
+            direction: Direction::East,
+        }
+    }
+
+    pub fn rotate(&mut self, dir: RelDirection, deg: u32) {
+        let new_direction = (self.direction as u32 + quarter_turns(dir, deg)) % 4;
+        self.direction = Direction::from_u32(new_direction).unwrap();
+    }
+}
+
+impl Navigate for Ship {
+    fn apply(&mut self, action: &Action) -> Result<(), OutOfRange> {
+        let out_of_range = OutOfRange { action: *action };
+        match *action {
+            Action::Move(dir, mag) => {
+                self.position = translate(self.position, dir.unit(), mag).ok_or(out_of_range)?
+            }
+            Action::Turn(dir, deg) => self.rotate(dir, deg),
+            Action::Forward(mag) => {
+                self.position =
+                    translate(self.position, self.direction.unit(), mag).ok_or(out_of_range)?
+            }
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> (i32, i32) {
+        self.position
+    }
+}
+
+/// A ship steered by a waypoint. Moves and turns act on the waypoint, which is
+/// relative to the ship, and going forward moves the ship towards the
+/// waypoint that many times.
+pub struct WaypointShip {
+    pub position: (i32, i32),
+    pub waypoint: (i32, i32),
+}
+
+impl WaypointShip {
+    pub fn new() -> WaypointShip {
+        WaypointShip {
+            position: (0, 0),
+            waypoint: (10, 1),
+        }
+    }
+}
+
+/// `waypoint` turned clockwise `turns` times about the ship, or `None` if a
+/// coordinate can't be negated.
+fn rotated(mut waypoint: (i32, i32), turns: u32) -> Option<(i32, i32)> {
+    for _ in 0..turns {
+        waypoint = (waypoint.1, waypoint.0.checked_neg()?);
+    }
+    Some(waypoint)
+}
+
+impl Navigate for WaypointShip {
+    fn apply(&mut self, action: &Action) -> Result<(), OutOfRange> {
+        let out_of_range = OutOfRange { action: *action };
+        match *action {
+            Action::Move(dir, mag) => {
+                self.waypoint = translate(self.waypoint, dir.unit(), mag).ok_or(out_of_range)?
+            }
+            Action::Turn(dir, deg) => {
+                self.waypoint =
+                    rotated(self.waypoint, quarter_turns(dir, deg)).ok_or(out_of_range)?
+            }
+            Action::Forward(times) => {
+                self.position =
+                    translate(self.position, self.waypoint, times).ok_or(out_of_range)?
+            }
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> (i32, i32) {
+        self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_rotate() {
+        let mut ship = Ship::new();
+        assert_eq!(ship.direction, Direction::East);
+        ship.rotate(RelDirection::Left, 270);
+        assert_eq!(ship.direction, Direction::South);
+        ship.rotate(RelDirection::Right, 90);
+        assert_eq!(ship.direction, Direction::West);
+        ship.rotate(RelDirection::Right, 180);
+        assert_eq!(ship.direction, Direction::East);
+    }
+
+    #[test]
+    fn test_waypoint() {
+        let mut ship = WaypointShip::new();
+        ship.apply(&Action::Forward(10)).unwrap();
+        assert_eq!(ship.position, (100, 10));
+        ship.apply(&Action::Move(Direction::North, 3)).unwrap();
+        assert_eq!(ship.waypoint, (10, 4));
+        ship.apply(&Action::Forward(7)).unwrap();
+        assert_eq!(ship.position, (170, 38));
+        ship.apply(&Action::Turn(RelDirection::Right, 90)).unwrap();
+        assert_eq!(ship.waypoint, (4, -10));
+        ship.apply(&Action::Turn(RelDirection::Left, 270)).unwrap();
+        assert_eq!(ship.waypoint, (-10, -4));
+        ship.apply(&Action::Forward(11)).unwrap();
+        assert_eq!(ship.position, (60, -6));
+    }
+
+    #[test]
+    fn test_execute() {
+        let actions = [
+            Action::Forward(10),
+            Action::Move(Direction::North, 3),
+            Action::Forward(7),
+            Action::Turn(RelDirection::Right, 90),
+            Action::Forward(11),
+        ];
+        assert_eq!(
+            execute(&mut Ship::new(), &actions).unwrap(),
+            Outcome {
+                position: (17, -8),
+                distance: 25
+            }
+        );
+        assert_eq!(
+            execute(&mut WaypointShip::new(), &actions)
+                .unwrap()
+                .distance,
+            286
+        );
+    }
+
+    #[test]
+    fn reports_out_of_range_actions() {
+        let far = Action::Forward(u32::MAX);
+        assert_eq!(
+            execute(&mut Ship::new(), &[far]),
+            Err(OutOfRange { action: far })
+        );
+        let mut ship = WaypointShip::new();
+        let big = Action::Move(Direction::East, i32::MAX as u32 - 10);
+        ship.apply(&big).unwrap();
+        assert_eq!(
+            ship.apply(&Action::Forward(2)),
+            Err(OutOfRange {
+                action: Action::Forward(2)
+            })
+        );
+        assert_eq!(ship.position, (0, 0));
+        assert_eq!(ship.apply(&big), Err(OutOfRange { action: big }));
+
+        // Distances past u32 are still reported exactly.
+        let actions = [
+            Action::Move(Direction::South, i32::MAX as u32),
+            Action::Move(Direction::South, 2),
+            Action::Move(Direction::West, i32::MAX as u32),
+            Action::Move(Direction::West, 11),
+            Action::Forward(1),
+        ];
+        let outcome = execute(&mut WaypointShip::new(), &actions).unwrap();
+        assert_eq!(outcome.position, (i32::MIN, i32::MIN));
+        assert_eq!(outcome.distance, 1 << 32);
+    }
+}
@@ -0,0 +1,316 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+pub mod repair;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    Accum,
+    Jump,
+    Noop,
+}
+
+impl Operation {
+    /// The operation a corrupted instruction should have been, if it can be corrupted at all.
+    pub fn flipped(&self) -> Option<Operation> {
+        match self {
+            Operation::Accum => None,
+            Operation::Jump => Some(Operation::Noop),
+            Operation::Noop => Some(Operation::Jump),
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Operation::Accum => "acc",
+            Operation::Jump => "jmp",
+            Operation::Noop => "nop",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Instruction {
+    pub op: Operation,
+    pub arg: i32,
+}
+
+impl Instruction {
+    /// Where this instruction sends control when executed at `pc`. May fall outside the program.
+    pub fn next_pc(&self, pc: usize) -> i64 {
+        match self.op {
+            Operation::Jump => pc as i64 + self.arg as i64,
+            Operation::Accum | Operation::Noop => pc as i64 + 1,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:+}", self.op, self.arg)
+    }
+}
+
+/// Errors produced while parsing a program. Line numbers are 1-based.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    MissingArgument { line: usize },
+    InvalidOperation { line: usize, op: String },
+    InvalidArgument { line: usize, arg: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingArgument { line } => {
+                write!(f, "line {}: missing argument", line)
+            }
+            ParseError::InvalidOperation { line, op } => {
+                write!(f, "line {}: invalid operation '{}'", line, op)
+            }
+            ParseError::InvalidArgument { line, arg } => {
+                write!(f, "line {}: invalid argument '{}'", line, arg)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Why `Program::exec` could not execute an instruction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExecError {
+    /// There is no instruction at `pc`, e.g. because the program already terminated.
+    NoInstruction { pc: usize },
+    /// The instruction would move the pc to `target`, outside of `0..=len`.
+    OutOfBounds { target: i64 },
+}
+
+/// How a call to `Program::run` stopped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunResult {
+    /// The pc landed exactly one past the last instruction.
+    Terminated(i32),
+    /// The instruction at `pc` was about to run a second time.
+    InfiniteLoop { acc: i32, pc: usize },
+    /// A jump targeted `pc`, which is neither an instruction nor the end of the program.
+    OutOfBounds { acc: i32, pc: i64 },
+}
+
+#[derive(Clone, Debug)]
+pub struct Program {
+    pub instructions: Vec<Instruction>,
+    pub accumulator: i32,
+    pub pc: usize,
+}
+
+pub fn parse_instruction(line_num: usize, line: &str) -> Result<Instruction, ParseError> {
+    let mut fields = line.split_whitespace();
+    let op_str = fields.next().unwrap_or("");
+    let op = match op_str {
+        "acc" => Operation::Accum,
+        "jmp" => Operation::Jump,
+        "nop" => Operation::Noop,
+        _ => {
+            return Err(ParseError::InvalidOperation {
+                line: line_num,
+                op: op_str.to_string(),
+            })
+        }
+    };
+    let arg_str = fields
+        .next()
+        .ok_or(ParseError::MissingArgument { line: line_num })?;
+    let arg = arg_str
+        .parse::<i32>()
+        .map_err(|_| ParseError::InvalidArgument {
+            line: line_num,
+            arg: arg_str.to_string(),
+        })?;
+
+    Ok(Instruction { op, arg })
+}
+
+impl FromStr for Program {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let instructions = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| parse_instruction(idx + 1, line))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Program::new(instructions))
+    }
+}
+
+impl Program {
+    pub fn new(instructions: Vec<Instruction>) -> Program {
+        Program {
+            instructions,
+            accumulator: 0,
+            pc: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    pub fn reset(&mut self) {
+        self.accumulator = 0;
+        self.pc = 0;
+    }
+
+    /// Where the instruction at `pc` sends control next, or `None` if there is
+    /// no instruction at `pc`. The target may fall outside the program.
+    pub fn next_pc(&self, pc: usize) -> Option<i64> {
+        self.instructions.get(pc).map(|inst| inst.next_pc(pc))
+    }
+
+    /// Executes the instruction at `pc`, leaving state untouched on error.
+    pub fn exec(&mut self) -> Result<(), ExecError> {
+        let target = self
+            .next_pc(self.pc)
+            .ok_or(ExecError::NoInstruction { pc: self.pc })?;
+        if target < 0 || target > self.len() as i64 {
+            return Err(ExecError::OutOfBounds { target });
+        }
+        let inst = &self.instructions[self.pc];
+        if inst.op == Operation::Accum {
+            self.accumulator += inst.arg;
+        }
+        self.pc = target as usize;
+        Ok(())
+    }
+
+    /// Runs from the current state until the program terminates, revisits an
+    /// instruction, or jumps out of bounds.
+    pub fn run(&mut self) -> RunResult {
+        self.run_traced().0
+    }
+
+    /// Like `run`, but also returns every pc executed, in order.
+    pub fn run_traced(&mut self) -> (RunResult, Vec<usize>) {
+        let mut visited = vec![false; self.len()];
+        let mut path = Vec::new();
+        let result = loop {
+            if self.pc == self.len() {
+                break RunResult::Terminated(self.accumulator);
+            }
+            match visited.get_mut(self.pc) {
+                Some(true) => {
+                    break RunResult::InfiniteLoop {
+                        acc: self.accumulator,
+                        pc: self.pc,
+                    }
+                }
+                Some(seen) => {
+                    *seen = true;
+                    path.push(self.pc);
+                }
+                None => {
+                    break RunResult::OutOfBounds {
+                        acc: self.accumulator,
+                        pc: self.pc as i64,
+                    }
+                }
+            }
+            match self.exec() {
+                Ok(()) => {}
+                Err(ExecError::OutOfBounds { target }) => {
+                    break RunResult::OutOfBounds {
+                        acc: self.accumulator,
+                        pc: target,
+                    }
+                }
+                Err(ExecError::NoInstruction { pc }) => {
+                    break RunResult::OutOfBounds {
+                        acc: self.accumulator,
+                        pc: pc as i64,
+                    }
+                }
+            }
+        };
+        (result, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str =
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n";
+
+    #[test]
+    fn run_detects_loop() {
+        let mut program: Program = EXAMPLE.parse().unwrap();
+        assert_eq!(program.run(), RunResult::InfiniteLoop { acc: 5, pc: 1 });
+    }
+
+    #[test]
+    fn run_terminates_and_out_of_bounds() {
+        let mut program: Program = EXAMPLE.parse().unwrap();
+        program.instructions[7].op = Operation::Noop;
+        assert_eq!(program.run(), RunResult::Terminated(8));
+
+        let mut program: Program = "acc +2\njmp -5\n".parse().unwrap();
+        assert_eq!(program.run(), RunResult::OutOfBounds { acc: 2, pc: -4 });
+    }
+
+    #[test]
+    fn run_traced_records_path() {
+        let mut program: Program = EXAMPLE.parse().unwrap();
+        let (result, path) = program.run_traced();
+        assert_eq!(result, RunResult::InfiniteLoop { acc: 5, pc: 1 });
+        assert_eq!(path, vec![0, 1, 2, 6, 7, 3, 4]);
+    }
+
+    #[test]
+    fn exec_after_termination() {
+        let mut program: Program = "acc +2\nnop +0\n".parse().unwrap();
+        assert_eq!(program.run(), RunResult::Terminated(2));
+        assert_eq!(program.next_pc(program.pc), None);
+        assert_eq!(program.exec(), Err(ExecError::NoInstruction { pc: 2 }));
+        assert_eq!(program.accumulator, 2);
+
+        program.pc = 5;
+        assert_eq!(program.run(), RunResult::OutOfBounds { acc: 2, pc: 5 });
+
+        let mut program: Program = "jmp +2\n".parse().unwrap();
+        assert_eq!(program.exec(), Err(ExecError::OutOfBounds { target: 2 }));
+        assert_eq!(program.pc, 0);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "nop +0\nfoo +1\n".parse::<Program>().unwrap_err(),
+            ParseError::InvalidOperation {
+                line: 2,
+                op: "foo".to_string()
+            }
+        );
+        assert_eq!(
+            "acc\n".parse::<Program>().unwrap_err(),
+            ParseError::MissingArgument { line: 1 }
+        );
+        assert_eq!(
+            "jmp +x\n".parse::<Program>().unwrap_err(),
+            ParseError::InvalidArgument {
+                line: 1,
+                arg: "+x".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+
+use crate::{Instruction, Operation, Program, RunResult};
+
+/// A single jmp/nop flip that makes the program terminate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Repair {
+    pub pc: usize,
+    pub replacement: Instruction,
+    /// Accumulator value once the repaired program terminates.
+    pub acc: i32,
+}
+
+fn target(pc: usize, inst: &Instruction, len: usize) -> Option<usize> {
+    let target = inst.next_pc(pc);
+    if target < 0 || target > len as i64 {
+        None
+    } else {
+        Some(target as usize)
+    }
+}
+
+/// For every pc in `0..=len`, the accumulator delta picked up on the way from
+/// that pc to termination, or `None` if the unmodified program never
+/// terminates from there. Index `len` is the termination point itself.
+///
+/// Every instruction has exactly one successor, so walking the reversed edges
+/// breadth-first from the termination point visits each pc at most once.
+pub fn acc_to_termination(program: &Program) -> Vec<Option<i32>> {
+    let len = program.len();
+    let mut predecessors = vec![Vec::new(); len + 1];
+    for (pc, inst) in program.instructions.iter().enumerate() {
+        if let Some(next) = target(pc, inst, len) {
+            predecessors[next].push(pc);
+        }
+    }
+
+    let mut acc_to_end = vec![None; len + 1];
+    acc_to_end[len] = Some(0);
+    let mut queue = VecDeque::new();
+    queue.push_back(len);
+    while let Some(curr) = queue.pop_front() {
+        let curr_acc = acc_to_end[curr].unwrap();
+        for &pred in predecessors[curr].iter() {
+            let inst = &program.instructions[pred];
+            let delta = if inst.op == Operation::Accum {
+                inst.arg
+            } else {
+                0
+            };
+            acc_to_end[pred] = Some(curr_acc + delta);
+            queue.push_back(pred);
+        }
+    }
+    acc_to_end
+}
+
+/// Finds every single-instruction jmp/nop flip that makes the program terminate.
+/// A program that already terminates has nothing to repair.
+///
+/// Only instructions on the path the unmodified program executes matter. When
+/// that program loops or jumps out of bounds, none of them can reach
+/// termination on their own, so no chain to termination passes through the
+/// flipped pc and a flip works exactly when its new target already reaches
+/// termination. Runs in O(n).
+pub fn find_repairs(program: &Program) -> Vec<Repair> {
+    let mut unmodified = program.clone();
+    unmodified.reset();
+    let (result, path) = unmodified.run_traced();
+    if let RunResult::Terminated(_) = result {
+        return Vec::new();
+    }
+
+    let len = program.len();
+    let acc_to_end = acc_to_termination(program);
+
+    let mut repairs = Vec::new();
+    let mut acc = 0;
+    for pc in path {
+        let inst = &program.instructions[pc];
+        if let Some(op) = inst.op.flipped() {
+            let replacement = Instruction { op, arg: inst.arg };
+            if let Some(acc_delta) = target(pc, &replacement, len).and_then(|t| acc_to_end[t]) {
+                repairs.push(Repair {
+                    pc,
+                    replacement,
+                    acc: acc + acc_delta,
+                });
+            }
+        }
+        if inst.op == Operation::Accum {
+            acc += inst.arg;
+        }
+    }
+    repairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_example_repair() {
+        let program: Program =
+            "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n"
+                .parse()
+                .unwrap();
+        let repairs = find_repairs(&program);
+        assert_eq!(
+            repairs,
+            vec![Repair {
+                pc: 7,
+                replacement: Instruction {
+                    op: Operation::Noop,
+                    arg: -4
+                },
+                acc: 8,
+            }]
+        );
+
+        let mut repaired = program.clone();
+        repaired.instructions[7] = repairs[0].replacement;
+        assert_eq!(repaired.run(), RunResult::Terminated(8));
+    }
+
+    #[test]
+    fn reports_every_repair() {
+        // Either flipping the nop at 0 or the self-jump at 1 escapes the loop.
+        let program: Program = "nop +3\njmp +0\nacc +1\nacc +2\n".parse().unwrap();
+        let repairs: Vec<(usize, i32)> = find_repairs(&program)
+            .iter()
+            .map(|r| (r.pc, r.acc))
+            .collect();
+        assert_eq!(repairs, vec![(0, 2), (1, 3)]);
+    }
+
+    #[test]
+    fn terminating_program_needs_no_repair() {
+        // Flipping the nop to `jmp +0` would loop, even though pc 0 reaches
+        // termination in the unmodified program.
+        let program: Program = "nop +0\nacc +1\n".parse().unwrap();
+        assert_eq!(find_repairs(&program), vec![]);
+    }
+}
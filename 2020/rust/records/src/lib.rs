@@ -0,0 +1,103 @@
+use std::io::{self, BufRead, BufReader, Lines, Read};
+
+/// A group of consecutive non-blank lines.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    /// 1-based line number of the record's first line.
+    pub start_line: usize,
+    /// The record's lines with trailing whitespace (including any `\r`) removed.
+    pub lines: Vec<String>,
+}
+
+impl Record {
+    /// Every whitespace-separated token across all of the record's lines.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().flat_map(|line| line.split_whitespace())
+    }
+}
+
+/// Lazily splits a reader into records separated by one or more blank lines.
+/// Lines containing only whitespace count as blank.
+pub struct Records<R> {
+    lines: Lines<BufReader<R>>,
+    line_num: usize,
+}
+
+pub fn records<R: Read>(reader: R) -> Records<R> {
+    Records {
+        lines: BufReader::new(reader).lines(),
+        line_num: 0,
+    }
+}
+
+impl<R: Read> Iterator for Records<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record: Option<Record> = None;
+        for line in &mut self.lines {
+            self.line_num += 1;
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err)),
+            };
+            let line = line.trim_end();
+            if line.is_empty() {
+                if record.is_some() {
+                    break;
+                }
+                continue;
+            }
+            let start_line = self.line_num;
+            record
+                .get_or_insert_with(|| Record {
+                    start_line,
+                    lines: Vec::new(),
+                })
+                .lines
+                .push(line.to_string());
+        }
+        record.map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_blank_lines() {
+        let input = "\nabc\n\na\r\nb  \r\n \t\n\n\nac\n";
+        let parsed: Vec<Record> = records(input.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(
+            parsed,
+            vec![
+                Record {
+                    start_line: 2,
+                    lines: vec!["abc".to_string()],
+                },
+                Record {
+                    start_line: 4,
+                    lines: vec!["a".to_string(), "b".to_string()],
+                },
+                Record {
+                    start_line: 9,
+                    lines: vec!["ac".to_string()],
+                },
+            ]
+        );
+        assert_eq!(parsed[1].fields().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn handles_missing_final_newline() {
+        let parsed: Vec<Record> = records("x:1 y:2\nz:3".as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(
+            parsed[0].fields().collect::<Vec<_>>(),
+            vec!["x:1", "y:2", "z:3"]
+        );
+    }
+}
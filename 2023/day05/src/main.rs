@@ -14,14 +14,14 @@ fn read_file_lines(filename: &str) -> Vec<String> {
     result
 }
 
-#[derive(Default,Debug)]
+#[derive(Default,Debug,Clone)]
 struct MapRange {
     destination_start: u64,
     source_start: u64,
     length: u64,
 }
 
-#[derive(Default,Debug)]
+#[derive(Default,Debug,Clone)]
 struct Mapping {
     source: String,
     destination: String,
@@ -36,21 +36,195 @@ impl Mapping {
                 return range.destination_start + (val - range.source_start)
             }
         }
-        return val
+        val
     }
 
-    fn map_dest_src(&self, val: u64) -> u64 {
-        for range in self.ranges.iter() {
-            let dest_end = range.destination_start + range.length;
-            if val >= range.destination_start && val < dest_end {
-                return range.source_start + (val - range.destination_start)
+    // Splits `range` wherever it crosses the edge of a MapRange and maps each
+    // piece. Values not covered by any MapRange map to themselves. Every piece
+    // is non-empty, so an empty range maps to nothing.
+    fn map_range(&self, range: &Range) -> Vec<Range> {
+        let mut sorted: Vec<&MapRange> = self
+            .ranges
+            .iter()
+            .filter(|map_range| map_range.length > 0)
+            .collect();
+        sorted.sort_by_key(|map_range| map_range.source_start);
+
+        let mut result = Vec::new();
+        let mut curr = range.start;
+        let end = range.start + range.length;
+        for map_range in sorted {
+            let source_end = map_range.source_start + map_range.length;
+            if source_end <= curr {
+                continue;
+            }
+            if curr >= end || map_range.source_start >= end {
+                break;
+            }
+            if map_range.source_start > curr {
+                result.push(Range {
+                    start: curr,
+                    length: map_range.source_start - curr,
+                });
+                curr = map_range.source_start;
+            }
+            let overlap_end = u64::min(end, source_end);
+            result.push(Range {
+                start: map_range.destination_start + (curr - map_range.source_start),
+                length: overlap_end - curr,
+            });
+            curr = overlap_end;
+        }
+        if curr < end {
+            result.push(Range {
+                start: curr,
+                length: end - curr,
+            });
+        }
+        result
+    }
+
+    fn map_ranges(&self, ranges: &[Range]) -> Vec<Range> {
+        ranges.iter().flat_map(|range| self.map_range(range)).collect()
+    }
+
+    // Every MapRange sorted by source, with the gaps between them (and up to
+    // u64::MAX) filled in by explicit identity ranges.
+    fn pieces(&self) -> Vec<MapRange> {
+        let mut sorted: Vec<&MapRange> = self.ranges.iter().collect();
+        sorted.sort_by_key(|map_range| map_range.source_start);
+
+        let mut pieces = Vec::new();
+        let mut curr = 0;
+        for map_range in sorted {
+            if map_range.source_start > curr {
+                pieces.push(MapRange {
+                    destination_start: curr,
+                    source_start: curr,
+                    length: map_range.source_start - curr,
+                });
+            }
+            pieces.push(map_range.clone());
+            curr = map_range.source_start + map_range.length;
+        }
+        if curr < u64::MAX {
+            pieces.push(MapRange {
+                destination_start: curr,
+                source_start: curr,
+                length: u64::MAX - curr,
+            });
+        }
+        pieces
+    }
+
+    // A single mapping equivalent to applying `self` and then `next`. Each
+    // piece of `self` is split wherever its image crosses a piece of `next`.
+    fn then(&self, next: &Mapping) -> Mapping {
+        let mut ranges = Vec::new();
+        for piece in self.pieces() {
+            let image = Range {
+                start: piece.destination_start,
+                length: piece.length,
+            };
+            let mut source_start = piece.source_start;
+            for mapped in next.map_range(&image) {
+                if mapped.start != source_start {
+                    ranges.push(MapRange {
+                        destination_start: mapped.start,
+                        source_start,
+                        length: mapped.length,
+                    });
+                }
+                source_start += mapped.length;
             }
         }
-        return val
+        Mapping {
+            source: self.source.clone(),
+            destination: next.destination.clone(),
+            ranges,
+        }
+    }
+
+    // Maps destination values back to source values, or None if the mapping
+    // isn't one-to-one, i.e. if two pieces (counting the values that map to
+    // themselves) share a destination.
+    fn inverse(&self) -> Option<Mapping> {
+        let mut images: Vec<MapRange> = self
+            .pieces()
+            .into_iter()
+            .filter(|piece| piece.length > 0)
+            .collect();
+        images.sort_by_key(|piece| piece.destination_start);
+        for pair in images.windows(2) {
+            let image_end = pair[0].destination_start.checked_add(pair[0].length)?;
+            if image_end > pair[1].destination_start {
+                return None;
+            }
+        }
+        Some(Mapping {
+            source: self.destination.clone(),
+            destination: self.source.clone(),
+            ranges: self
+                .ranges
+                .iter()
+                .map(|range| MapRange {
+                    destination_start: range.source_start,
+                    source_start: range.destination_start,
+                    length: range.length,
+                })
+                .collect(),
+        })
     }
 }
 
-#[derive(Default,Debug)]
+// Composes a chain of mappings, e.g. seed-to-soil, soil-to-fertilizer, ...,
+// into one mapping from the first source to the last destination.
+fn compose(maps: &[&Mapping]) -> Option<Mapping> {
+    let (first, rest) = maps.split_first()?;
+    Some(rest.iter().fold((*first).clone(), |acc, map| acc.then(map)))
+}
+
+struct Almanac {
+    maps: Vec<Mapping>,
+}
+
+impl Almanac {
+    // The chain of mappings leading from `from` to `to`, following each
+    // mapping's destination to the next mapping's source.
+    fn chain(&self, from: &str, to: &str) -> Option<Vec<&Mapping>> {
+        let mut chain = Vec::new();
+        let mut stage = from;
+        while stage != to {
+            let map = self.maps.iter().find(|map| map.source == stage)?;
+            chain.push(map);
+            stage = map.destination.as_str();
+            if chain.len() > self.maps.len() {
+                return None;
+            }
+        }
+        Some(chain)
+    }
+
+    // A single mapping between any two stages, e.g. "soil" to "humidity".
+    // Stages earlier in the almanac than `from` are reached by inverting the
+    // chain in the other direction, which fails unless it is one-to-one.
+    fn mapping(&self, from: &str, to: &str) -> Option<Mapping> {
+        if from == to {
+            return Some(Mapping {
+                source: from.to_string(),
+                destination: to.to_string(),
+                ranges: Vec::new(),
+            });
+        }
+        if let Some(chain) = self.chain(from, to) {
+            return compose(&chain);
+        }
+        let chain = self.chain(to, from)?;
+        compose(&chain).and_then(|mapping| mapping.inverse())
+    }
+}
+
+#[derive(Default,Debug,Clone,PartialEq)]
 struct Range {
     start: u64,
     length: u64,
@@ -104,32 +278,24 @@ impl Seeds {
             |chunk| {
                 let mut it = chunk.into_iter();
                 Range{
-                    start: it.next().unwrap().clone(),
-                    length: it.next().unwrap().clone(),
+                    start: *it.next().unwrap(),
+                    length: *it.next().unwrap(),
                 }
-        }).collect();
+        }).filter(|range| range.length > 0).collect();
         Seeds{
             seeds
         }
     }
 
-    fn is_valid(&self, possible_seed: u64) -> bool {
-        for seed in self.seeds.iter() {
-            if possible_seed >= seed.start && possible_seed < seed.start + seed.length {
-                return true
-            }
-        }
-        return false
-    }
 }
 
-fn maps_from_lines<I>(lines_iter: I) -> Vec<Mapping>
+fn almanac_from_lines<I>(lines_iter: I) -> Almanac
 where
     I: Iterator<Item = String>,
 {
     let mut maps = Vec::<Mapping>::new();
     let map_re = Regex::new(r"^([a-z]+)-to-([a-z]+) map:$").unwrap();
-    for line in lines_iter.filter(|x| x.trim().len() > 0) {
+    for line in lines_iter.filter(|x| !x.trim().is_empty()) {
         if let Some(caps) = map_re.captures(line.as_str()) {
             let source = caps.get(1).unwrap().as_str().to_string();
             let destination = caps.get(2).unwrap().as_str().to_string();
@@ -151,56 +317,195 @@ where
             }
         }
     }
-    return maps;
+    Almanac { maps }
 }
 
-fn part1(lines: Vec<String>) {
+fn part1(lines: Vec<String>) -> Result<u64, String> {
     let mut lines_iter = lines.into_iter();
     let seeds = Seeds::for_part1(lines_iter.next().unwrap());
-    let maps = maps_from_lines(lines_iter);
+    let almanac = almanac_from_lines(lines_iter);
+    let seed_to_location = almanac
+        .mapping("seed", "location")
+        .ok_or("No mapping from seed to location")?;
 
     let mut lowest_location = u64::MAX;
     for seed in seeds.into_iter() {
-        let mut curr_value: u64 = seed.clone();
-        for map in maps.iter() {
-            curr_value = map.map_src_dest(curr_value);
-        }
-        lowest_location = u64::min(lowest_location, curr_value);
+        lowest_location = u64::min(lowest_location, seed_to_location.map_src_dest(seed));
     }
-    println!("Part1: {}", lowest_location);
+    Ok(lowest_location)
 }
 
-fn part2(lines: Vec<String>) {
+fn part2(lines: Vec<String>) -> Result<u64, String> {
     let mut lines_iter = lines.into_iter();
     let seeds = Seeds::for_part2(lines_iter.next().unwrap());
-    let maps = maps_from_lines(lines_iter);
+    let almanac = almanac_from_lines(lines_iter);
+    let seed_to_location = almanac
+        .mapping("seed", "location")
+        .ok_or("No mapping from seed to location")?;
 
-    let mut lowest_location = 0;
-    for location in 0..u64::MAX {
-        let mut curr_value = location;
-        for map in maps.iter().rev() {
-            curr_value = map.map_dest_src(curr_value);
-        }
-        if seeds.is_valid(curr_value) {
-            lowest_location = location;
-            break;
+    let ranges = seed_to_location.map_ranges(&seeds.seeds);
+    let lowest_location = ranges
+        .iter()
+        .map(|range| range.start)
+        .min()
+        .ok_or("No seeds to plant")?;
+    Ok(lowest_location)
+}
+
+// Prints a lookup table from one stage to another, e.g. "soil" to "humidity",
+// in the almanac's own format. Any values given are mapped as well.
+fn lookup(lines: Vec<String>, from: &str, to: &str, values: &[String]) {
+    let almanac = almanac_from_lines(lines.into_iter().skip(1));
+    let Some(mapping) = almanac.mapping(from, to) else {
+        eprintln!("No mapping from {} to {}", from, to);
+        std::process::exit(1);
+    };
+
+    if values.is_empty() {
+        println!("{}-to-{} map:", mapping.source, mapping.destination);
+        let mut ranges = mapping.ranges.clone();
+        ranges.sort_by_key(|range| range.source_start);
+        for range in ranges.iter() {
+            println!("{} {} {}", range.destination_start, range.source_start, range.length);
         }
     }
-
-    println!("Part2: {}", lowest_location);
+    for value in values {
+        let Ok(value) = value.parse::<u64>() else {
+            eprintln!("Invalid value '{}'", value);
+            std::process::exit(1);
+        };
+        println!("{} {} -> {} {}", from, value, to, mapping.map_src_dest(value));
+    }
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
-        eprintln!("Usage: {} <filename>", args[0]);
+    if args.len() != 2 && args.len() < 4 {
+        eprintln!("Usage: {} <filename> [<from> <to> [value...]]", args[0]);
         std::process::exit(1);
     }
 
     let filename = &args[1];
 
     let lines = read_file_lines(filename);
-    part1(lines.clone());
-    part2(lines.clone());
+    if args.len() >= 4 {
+        lookup(lines, &args[2], &args[3], &args[4..]);
+        return;
+    }
+    for (part, result) in [("Part1", part1(lines.clone())), ("Part2", part2(lines))] {
+        match result {
+            Ok(lowest_location) => println!("{}: {}", part, lowest_location),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let lines = read_file_lines("src/test");
+        assert_eq!(part1(lines.clone()), Ok(35));
+        assert_eq!(part2(lines), Ok(46));
+    }
+
+    #[test]
+    fn test_map_range() {
+        let map = Mapping {
+            source: "seed".to_string(),
+            destination: "soil".to_string(),
+            ranges: vec![
+                MapRange { destination_start: 50, source_start: 98, length: 2 },
+                MapRange { destination_start: 52, source_start: 50, length: 48 },
+            ],
+        };
+        assert_eq!(
+            map.map_range(&Range { start: 40, length: 70 }),
+            vec![
+                Range { start: 40, length: 10 },
+                Range { start: 52, length: 48 },
+                Range { start: 50, length: 2 },
+                Range { start: 100, length: 10 },
+            ]
+        );
+        assert_eq!(
+            map.map_range(&Range { start: 60, length: 5 }),
+            vec![Range { start: 62, length: 5 }]
+        );
+        assert_eq!(map.map_range(&Range { start: 60, length: 0 }), vec![]);
+    }
+
+    #[test]
+    fn test_empty_seed_ranges() {
+        let mut lines = read_file_lines("src/test");
+        // Seed 0 would reach location 22 if the empty range were planted.
+        lines[0] = "seeds: 79 14 55 13 0 0".to_string();
+        assert_eq!(part2(lines.clone()), Ok(46));
+        lines[0] = "seeds: 0 0".to_string();
+        assert!(part2(lines.clone()).is_err());
+        lines[0] = "seeds:".to_string();
+        assert!(part2(lines).is_err());
+    }
+
+    #[test]
+    fn test_compose() {
+        let mut lines = read_file_lines("src/test").into_iter();
+        lines.next();
+        let almanac = almanac_from_lines(lines);
+        let composed = almanac.mapping("seed", "location").unwrap();
+        assert_eq!(composed.source, "seed");
+        assert_eq!(composed.destination, "location");
+        let inverse = composed.inverse().unwrap();
+        for seed in 0..200 {
+            let chained = almanac.maps.iter().fold(seed, |val, map| map.map_src_dest(val));
+            assert_eq!(composed.map_src_dest(seed), chained);
+            assert_eq!(inverse.map_src_dest(chained), seed);
+        }
+    }
+
+    #[test]
+    fn test_inverse_needs_one_to_one() {
+        let mapping = |ranges| Mapping {
+            source: "seed".to_string(),
+            destination: "soil".to_string(),
+            ranges,
+        };
+        let swap = mapping(vec![
+            MapRange { destination_start: 10, source_start: 0, length: 5 },
+            MapRange { destination_start: 0, source_start: 10, length: 5 },
+        ]);
+        let inverse = swap.inverse().unwrap();
+        assert_eq!(inverse.map_src_dest(12), 2);
+        assert_eq!(inverse.map_src_dest(20), 20);
+        // Both sources land on 10..15.
+        let overlapping = mapping(vec![
+            MapRange { destination_start: 10, source_start: 0, length: 5 },
+            MapRange { destination_start: 10, source_start: 20, length: 5 },
+        ]);
+        assert!(overlapping.inverse().is_none());
+        // 0..5 also maps to itself.
+        let onto_gap = mapping(vec![
+            MapRange { destination_start: 0, source_start: 10, length: 5 },
+        ]);
+        assert!(onto_gap.inverse().is_none());
+    }
+
+    #[test]
+    fn test_stage_lookup() {
+        let mut lines = read_file_lines("src/test").into_iter();
+        lines.next();
+        let almanac = almanac_from_lines(lines);
+        // Soil 81 is fertilizer 81, water 81, light 74, temperature 78, humidity 78.
+        let soil_to_humidity = almanac.mapping("soil", "humidity").unwrap();
+        assert_eq!(soil_to_humidity.map_src_dest(81), 78);
+        let humidity_to_soil = almanac.mapping("humidity", "soil").unwrap();
+        assert_eq!(humidity_to_soil.map_src_dest(78), 81);
+        assert!(almanac.mapping("seed", "nowhere").is_none());
+    }
 }